@@ -0,0 +1,100 @@
+#!/usr/bin/env python3
+"""Generates emoji.tsv from the Unicode emoji-test.txt and the CLDR annotations.
+
+    ./generate-emoji.py emoji-test.txt annotations/en.xml annotationsDerived/en.xml > emoji.tsv
+
+emoji-test.txt is at https://unicode.org/Public/emoji/latest/emoji-test.txt and the
+annotations are in common/annotations and common/annotationsDerived of the CLDR
+release (https://github.com/unicode-org/cldr), the derived ones cover sequences
+like skin tones and families. unicode.tsv should come from the same Unicode release,
+see generate-unicode.py.
+"""
+
+import os
+import re
+import sys
+import xml.etree.ElementTree as ElementTree
+
+SKIN_TONES = [
+    "light skin tone",
+    "medium-light skin tone",
+    "medium skin tone",
+    "medium-dark skin tone",
+    "dark skin tone",
+]
+SKIN_TONE_MODIFIERS = {chr(cp) for cp in range(0x1F3FB, 0x1F400)}
+VARIATION_SELECTOR_EMOJI = "️"
+
+LINE_REGEX = re.compile(r"^[0-9A-F ]+;\s*([a-z-]+)\s*#\s*(\S+)\s+E[0-9.]+\s+(.+)$")
+
+
+def read_emoji_test(path):
+    version = None
+    emoji = []
+    with open(path, encoding="utf-8") as f:
+        for line in f:
+            line = line.strip()
+            if line.startswith("# Version:"):
+                version = line.split(":", 1)[1].strip()
+                continue
+
+            match = LINE_REGEX.match(line)
+            if match and match.group(1) == "fully-qualified":
+                emoji.append((match.group(2), match.group(3)))
+    return version, emoji
+
+
+# keywords by emoji, CLDR leaves the variation selector out of them
+def read_annotations(paths):
+    keywords = {}
+    for path in paths:
+        for annotation in ElementTree.parse(path).getroot().iter("annotation"):
+            if annotation.get("type") == "tts" or not annotation.text:
+                continue
+            cp = annotation.get("cp").replace(VARIATION_SELECTOR_EMOJI, "")
+            words = keywords.setdefault(cp, [])
+            for phrase in annotation.text.split("|"):
+                for word in phrase.lower().split():
+                    if word not in words:
+                        words.append(word)
+    return keywords
+
+
+def main():
+    if len(sys.argv) < 2:
+        sys.exit(__doc__)
+
+    version, emoji = read_emoji_test(sys.argv[1])
+    keywords = read_annotations(sys.argv[2:])
+    by_name = {name: e for e, name in emoji}
+
+    print("# emoji\tname\tkeywords\tskin tone variants, from light to dark")
+    print(
+        "# generated by generate-emoji.py from emoji-test.txt (Emoji {}), keywords from {}".format(
+            version, ", ".join(os.path.basename(it) for it in sys.argv[2:]) or "nothing"
+        )
+    )
+    for e, name in emoji:
+        # those come with the emoji they modify
+        if SKIN_TONE_MODIFIERS.intersection(e):
+            continue
+
+        # "waving hand: light skin tone" or "kiss: woman, man, light skin tone",
+        # sequences with a different tone per person are left out
+        variants = [
+            by_name.get("{}: {}".format(name, tone)) or by_name.get("{}, {}".format(name, tone))
+            for tone in SKIN_TONES
+        ]
+        variants = variants if all(variants) else []
+
+        name_words = set(re.split(r"[\s:,-]+", name.lower()))
+        words = [
+            it
+            for it in keywords.get(e.replace(VARIATION_SELECTOR_EMOJI, ""), [])
+            if it not in name_words
+        ]
+        print("\t".join([e, name, " ".join(words), " ".join(variants)]))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+"""Generates unicode.tsv from the Unicode character database.
+
+    ./generate-unicode.py 16.0.0 UnicodeData.txt > unicode.tsv
+
+UnicodeData.txt is at https://unicode.org/Public/<version>/ucd/UnicodeData.txt, it
+should be the same release as the emoji-test.txt emoji.tsv is generated from.
+"""
+
+import sys
+
+# names made of the prefix and the codepoint, those are found by codepoint anyway
+DERIVED_NAME_PREFIXES = (
+    "CJK UNIFIED IDEOGRAPH-",
+    "CJK COMPATIBILITY IDEOGRAPH-",
+    "TANGUT IDEOGRAPH-",
+    "TANGUT COMPONENT-",
+    "KHITAN SMALL SCRIPT CHARACTER-",
+    "NUSHU CHARACTER-",
+    "EGYPTIAN HIEROGLYPH-",
+    "HANGUL SYLLABLE ",
+)
+
+
+def read_names(path):
+    names = []
+    with open(path, encoding="utf-8") as f:
+        for line in f:
+            fields = line.split(";")
+            if len(fields) < 2:
+                continue
+
+            codepoint, name = fields[0], fields[1]
+            # "<control>" and the "<CJK Ideograph, First>" ranges have no name of their own
+            if name.startswith("<") or name.startswith(DERIVED_NAME_PREFIXES):
+                continue
+            names.append((codepoint, name))
+    return names
+
+
+def main():
+    if len(sys.argv) != 3:
+        sys.exit(__doc__)
+
+    version = sys.argv[1]
+    names = read_names(sys.argv[2])
+
+    print("# codepoint\tname")
+    print("# from the Unicode {} character database, without the names that are just the".format(version))
+    print("# codepoint (CJK ideographs, hangul syllables...), those are still found by codepoint")
+    for codepoint, name in names:
+        print("{}\t{}".format(codepoint, name))
+
+
+if __name__ == "__main__":
+    main()
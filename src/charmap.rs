@@ -0,0 +1,291 @@
+use std::{cell::RefCell, fs, io, path::PathBuf};
+
+use crate::config;
+
+// bundled so searching never needs the network, see the headers for where they come from
+const EMOJI_DATA: &str = include_str!("../data/emoji.tsv");
+const UNICODE_DATA: &str = include_str!("../data/unicode.tsv");
+
+const RECENT_FILE_NAME: &str = "recent-characters";
+const RECENT_MAX_ENTRIES: usize = 50;
+
+// in the order the variants are listed in the emoji data
+const SKIN_TONES: [&str; 5] = [
+    "light skin tone",
+    "medium-light skin tone",
+    "medium skin tone",
+    "medium-dark skin tone",
+    "dark skin tone",
+];
+
+#[derive(Debug, Clone)]
+pub struct Emoji {
+    pub emoji: String,
+    pub name: String,
+    pub keywords: Vec<String>,
+    // the same emoji with each skin tone, empty when it doesn't take them
+    skin_tone_variants: Vec<String>,
+    // lowercase name, what queries are matched against ("flag: Brazil")
+    search_name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Character {
+    pub character: char,
+    pub name: String,
+}
+
+impl Emoji {
+    /// The emoji with each skin tone modifier, with the tone name
+    pub fn skin_tone_variants(&self) -> Vec<(String, &'static str)> {
+        self.skin_tone_variants
+            .iter()
+            .cloned()
+            .zip(SKIN_TONES)
+            .collect()
+    }
+}
+
+/// "U+1F44D U+1F3FD"
+pub fn codepoints(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub fn html_entity(text: &str) -> String {
+    text.chars().map(|c| format!("&#x{:X};", c as u32)).collect()
+}
+
+pub fn rust_escape(text: &str) -> String {
+    text.chars().map(|c| format!("\\u{{{:X}}}", c as u32)).collect()
+}
+
+// "U+2192", "u+2192" or "0x2192"
+fn parse_codepoint(query: &str) -> Option<char> {
+    let query = query.trim();
+    let hex = query
+        .strip_prefix("U+")
+        .or_else(|| query.strip_prefix("u+"))
+        .or_else(|| query.strip_prefix("0x"))?;
+
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
+
+// every word of the query has to start one of the words, lower is better
+fn match_score(query_words: &[&str], name: &str, keywords: &[String]) -> Option<usize> {
+    if query_words.is_empty() {
+        return Some(3);
+    }
+
+    let query = query_words.join(" ");
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(&query) {
+        return Some(1);
+    }
+
+    // emoji names also have "flag: Brazil" or "family: man, woman, girl"
+    let name_words: Vec<&str> = name
+        .split([' ', '-', ':', ','])
+        .filter(|it| !it.is_empty())
+        .collect();
+    let starts_a_word = |words: &[&str], query_word: &str| {
+        words.iter().any(|word| word.starts_with(query_word))
+    };
+
+    if query_words.iter().all(|q| starts_a_word(&name_words, q)) {
+        return Some(2);
+    }
+    let keywords: Vec<&str> = keywords.iter().map(|it| it.as_str()).collect();
+    if query_words
+        .iter()
+        .all(|q| starts_a_word(&name_words, q) || starts_a_word(&keywords, q))
+    {
+        return Some(3);
+    }
+
+    None
+}
+
+fn load_emoji() -> Vec<Emoji> {
+    EMOJI_DATA
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let emoji = columns.next()?.to_string();
+            let name = columns.next()?.to_string();
+            let keywords = columns
+                .next()?
+                .split_whitespace()
+                .map(|it| it.to_string())
+                .collect();
+            let skin_tone_variants: Vec<String> = columns
+                .next()?
+                .split_whitespace()
+                .map(|it| it.to_string())
+                .collect();
+            // a partial list would pair variants with the wrong tone names
+            let skin_tone_variants = if skin_tone_variants.len() == SKIN_TONES.len() {
+                skin_tone_variants
+            } else {
+                vec![]
+            };
+
+            Some(Emoji {
+                search_name: name.to_lowercase(),
+                emoji,
+                name,
+                keywords,
+                skin_tone_variants,
+            })
+        })
+        .collect()
+}
+
+fn load_characters() -> Vec<Character> {
+    UNICODE_DATA
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (codepoint, name) = line.split_once('\t')?;
+            Some(Character {
+                character: char::from_u32(u32::from_str_radix(codepoint, 16).ok()?)?,
+                name: name.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+/// Emoji and the unicode character names, with the last used ones kept in the data dir
+/// so they can be shown first
+#[derive(Debug)]
+pub struct CharMap {
+    emoji: Vec<Emoji>,
+    characters: Vec<Character>,
+    recent: RefCell<Vec<String>>,
+    recent_path: PathBuf,
+}
+
+impl CharMap {
+    pub fn load() -> Self {
+        let recent_path = config::data_dir().join(RECENT_FILE_NAME);
+        let recent = fs::read_to_string(&recent_path)
+            .map(|content| content.lines().map(|it| it.to_string()).collect())
+            .unwrap_or_default();
+
+        Self {
+            emoji: load_emoji(),
+            characters: load_characters(),
+            recent: RefCell::new(recent),
+            recent_path,
+        }
+    }
+
+    pub fn matching_emoji(&self, query: &str, limit: usize) -> Vec<Emoji> {
+        let query = query.trim().to_lowercase();
+        let query_words: Vec<&str> = query.split_whitespace().collect();
+
+        let mut matches: Vec<(usize, usize, &Emoji)> = self
+            .emoji
+            .iter()
+            .filter_map(|it| {
+                let score = match_score(&query_words, &it.search_name, &it.keywords)?;
+                Some((self.recent_rank(&it.emoji), score, it))
+            })
+            .collect();
+        // shorter names are closer matches, without a query the bundled order is kept
+        matches.sort_by_key(|(recent, score, it)| {
+            let length = if query_words.is_empty() { 0 } else { it.search_name.len() };
+            (*recent, *score, length)
+        });
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, it)| it.clone())
+            .collect()
+    }
+
+    pub fn matching_characters(&self, query: &str, limit: usize) -> Vec<Character> {
+        if let Some(c) = parse_codepoint(query) {
+            return vec![self.character(c)];
+        }
+
+        let query = query.trim().to_lowercase();
+        let query_words: Vec<&str> = query.split_whitespace().collect();
+        if query_words.is_empty() {
+            return self
+                .recent
+                .borrow()
+                .iter()
+                .filter_map(|it| it.chars().next())
+                .take(limit)
+                .map(|c| self.character(c))
+                .collect();
+        }
+
+        let mut matches: Vec<(usize, usize, &Character)> = self
+            .characters
+            .iter()
+            .filter_map(|it| {
+                let score = match_score(&query_words, &it.name, &[])?;
+                Some((self.recent_rank(&it.character.to_string()), score, it))
+            })
+            .collect();
+        // stable, so ties stay in codepoint order
+        matches.sort_by_key(|(recent, score, it)| (*recent, *score, it.name.len()));
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, it)| it.clone())
+            .collect()
+    }
+
+    /// Moves the character to the front of the recently used ones
+    pub fn record_recent(&self, text: &str) {
+        let mut recent = self.recent.borrow_mut();
+        recent.retain(|it| it != text);
+        recent.insert(0, text.to_string());
+        recent.truncate(RECENT_MAX_ENTRIES);
+
+        if let Err(e) = self.save_recent(&recent) {
+            println!(
+                "unable to save recent characters to {}: {}",
+                self.recent_path.to_string_lossy(),
+                e
+            );
+        }
+    }
+
+    fn save_recent(&self, recent: &[String]) -> io::Result<()> {
+        if let Some(dir) = self.recent_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.recent_path, recent.join("\n"))
+    }
+
+    // never used ones all share the last position
+    fn recent_rank(&self, text: &str) -> usize {
+        let recent = self.recent.borrow();
+        recent
+            .iter()
+            .position(|it| it == text)
+            .unwrap_or(recent.len())
+    }
+
+    fn character(&self, c: char) -> Character {
+        self.characters
+            .iter()
+            .find(|it| it.character == c)
+            .cloned()
+            .unwrap_or(Character {
+                character: c,
+                name: codepoints(&c.to_string()),
+            })
+    }
+}
@@ -0,0 +1,489 @@
+use std::{
+    env, fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    os::unix::process::CommandExt,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use wl_clipboard_rs::{
+    copy::{self, MimeSource, MimeType, Options, Source},
+    paste::{self, ClipboardType, Seat},
+};
+use x11rb::{
+    CURRENT_TIME, NONE,
+    connection::{Connection, RequestConnection},
+    protocol::{
+        Event,
+        xproto::{
+            Atom, AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, CreateWindowAux,
+            EventMask, PropMode, Property, SELECTION_NOTIFY_EVENT, SelectionNotifyEvent, Window,
+            WindowClass,
+        },
+    },
+    wrapper::ConnectionExt as _,
+};
+
+/// Flag of the process that owns the X11 clipboard after the launcher closes
+pub const X11_SERVE_FLAG: &str = "--serve-x11-clipboard";
+
+/// Set by KeePassXC, Bitwarden and others on the passwords they copy,
+/// clipboard managers don't record what has it
+pub const PASSWORD_MANAGER_HINT: &str = "x-kde-passwordManagerHint";
+
+const TEXT_MIME_TYPE: &str = "text/plain";
+// what X11 apps ask for when they want plain text
+const X11_TEXT_TARGETS: [&str; 4] = ["UTF8_STRING", "STRING", "TEXT", "text/plain;charset=utf-8"];
+// properties bigger than this are sent in chunks with the INCR protocol
+const X11_MAX_CHUNK_SIZE: usize = 256 * 1024;
+// how long the clipboard owner has to answer when reading from it
+const X11_PASTE_TIMEOUT: Duration = Duration::from_secs(1);
+const X11_PASTE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub enum ClipboardError {
+    NoDisplay,
+    NoText,
+    Wayland(copy::Error),
+    WaylandPaste(paste::Error),
+    X11(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClipboardError::NoDisplay => write!(f, "neither Wayland nor X11 are available"),
+            ClipboardError::NoText => write!(f, "the clipboard has no text"),
+            ClipboardError::Wayland(e) => write!(f, "{}", e),
+            ClipboardError::WaylandPaste(e) => write!(f, "{}", e),
+            ClipboardError::X11(e) => write!(f, "{}", e),
+            ClipboardError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for ClipboardError {
+    fn from(e: io::Error) -> Self {
+        ClipboardError::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+fn x11_error(e: impl fmt::Display) -> ClipboardError {
+    ClipboardError::X11(e.to_string())
+}
+
+/// The same content in one or more formats, apps pasting it pick the one they prefer
+#[derive(Debug, Clone)]
+pub struct ClipboardData {
+    // (MIME type, content), in order of preference
+    offers: Vec<(String, Vec<u8>)>,
+}
+
+impl ClipboardData {
+    pub fn new(mime_type: &str, content: Vec<u8>) -> Self {
+        Self {
+            offers: vec![(mime_type.to_string(), content)],
+        }
+    }
+
+    pub fn text(text: &str) -> Self {
+        Self::new(TEXT_MIME_TYPE, text.as_bytes().to_vec())
+    }
+
+    /// Text that shouldn't end up in the clipboard history, like a generated password
+    pub fn secret(text: &str) -> Self {
+        Self::text(text).with(PASSWORD_MANAGER_HINT, b"secret".to_vec())
+    }
+
+    /// Formatted text, with a plain version for apps that don't take HTML
+    pub fn html(html: &str, text: &str) -> Self {
+        Self::new("text/html", html.as_bytes().to_vec()).with(TEXT_MIME_TYPE, text.as_bytes().to_vec())
+    }
+
+    /// Files, so file managers paste copies of them and everything else gets their paths
+    pub fn files(paths: &[String], uris: &[String]) -> Self {
+        let uri_list: String = uris.iter().map(|it| format!("{}\r\n", it)).collect();
+        // GNOME Files and others ignore text/uri-list if this isn't there
+        let gnome_files = format!("copy\n{}", uris.join("\n"));
+        Self::new("text/uri-list", uri_list.into_bytes())
+            .with("x-special/gnome-copied-files", gnome_files.into_bytes())
+            .with(TEXT_MIME_TYPE, paths.join("\n").into_bytes())
+    }
+
+    pub fn with(mut self, mime_type: &str, content: Vec<u8>) -> Self {
+        self.offers.push((mime_type.to_string(), content));
+        self
+    }
+
+    fn get(&self, mime_type: &str) -> Option<&[u8]> {
+        self.offers
+            .iter()
+            .find(|(it, _)| it == mime_type)
+            .map(|(_, content)| content.as_slice())
+    }
+
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        for (mime_type, content) in &self.offers {
+            writeln!(writer, "{}", mime_type)?;
+            writeln!(writer, "{}", content.len())?;
+            writer.write_all(content)?;
+        }
+        writer.flush()
+    }
+
+    fn read_from(reader: &mut impl BufRead) -> io::Result<Self> {
+        let mut offers = Vec::new();
+        loop {
+            let mut mime_type = String::new();
+            if reader.read_line(&mut mime_type)? == 0 {
+                break;
+            }
+            let mut len = String::new();
+            reader.read_line(&mut len)?;
+            let len: usize = len
+                .trim()
+                .parse()
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid content length"))?;
+
+            let mut content = vec![0; len];
+            reader.read_exact(&mut content)?;
+            offers.push((mime_type.trim_end().to_string(), content));
+        }
+
+        Ok(Self { offers })
+    }
+}
+
+/// Sets the clipboard on Wayland, or on X11 when there's no Wayland or the compositor
+/// doesn't support the data control protocol (like GNOME, where Xwayland passes it on).
+///
+/// Either way the content is served by a separate process, so it's still there
+/// after the launcher exits.
+pub fn copy(data: ClipboardData) -> Result<()> {
+    let has_x11 = env::var_os("DISPLAY").is_some_and(|it| !it.is_empty());
+    if env::var_os("WAYLAND_DISPLAY").is_some_and(|it| !it.is_empty()) {
+        match copy_wayland(&data) {
+            Ok(()) => return Ok(()),
+            Err(e) if has_x11 => println!("unable to copy on Wayland, trying X11: {}", e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if has_x11 {
+        return spawn_x11_server(&data);
+    }
+
+    Err(ClipboardError::NoDisplay)
+}
+
+/// The text in the clipboard, from the same place `copy` would put it
+pub fn paste_text() -> Result<String> {
+    let has_x11 = env::var_os("DISPLAY").is_some_and(|it| !it.is_empty());
+    if env::var_os("WAYLAND_DISPLAY").is_some_and(|it| !it.is_empty()) {
+        match paste_text_wayland() {
+            Ok(text) => return Ok(text),
+            Err(ClipboardError::NoText) => return Err(ClipboardError::NoText),
+            Err(e) if has_x11 => println!("unable to paste on Wayland, trying X11: {}", e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if has_x11 {
+        return paste_text_x11();
+    }
+
+    Err(ClipboardError::NoDisplay)
+}
+
+fn paste_text_wayland() -> Result<String> {
+    let (mut reader, _) =
+        match paste::get_contents(ClipboardType::Regular, Seat::Unspecified, paste::MimeType::Text) {
+            Ok(contents) => contents,
+            Err(paste::Error::ClipboardEmpty | paste::Error::NoMimeType) => {
+                return Err(ClipboardError::NoText);
+            }
+            Err(e) => return Err(ClipboardError::WaylandPaste(e)),
+        };
+
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+// asks the owner to put it in a property of a window of our own, then reads it from there
+fn paste_text_x11() -> Result<String> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(x11_error)?;
+    let root = conn.setup().roots[screen_num].root;
+    let intern = |name: &str| -> Result<Atom> {
+        Ok(conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .atom)
+    };
+
+    let clipboard = intern("CLIPBOARD")?;
+    let utf8_string = intern("UTF8_STRING")?;
+    let incr = intern("INCR")?;
+    let property = intern("AUTOMATA_PASTE")?;
+
+    let window = create_x11_window(&conn, root)?;
+    conn.convert_selection(window, clipboard, utf8_string, property, CURRENT_TIME)
+        .map_err(x11_error)?;
+    conn.flush().map_err(x11_error)?;
+
+    let deadline = Instant::now() + X11_PASTE_TIMEOUT;
+    loop {
+        match conn.poll_for_event().map_err(x11_error)? {
+            Some(Event::SelectionNotify(e)) if e.requestor == window => {
+                if e.property == NONE {
+                    return Err(ClipboardError::NoText);
+                }
+                break;
+            }
+            Some(_) => continue,
+            None if Instant::now() >= deadline => {
+                return Err(x11_error("the clipboard owner didn't answer"));
+            }
+            None => thread::sleep(X11_PASTE_POLL_INTERVAL),
+        }
+    }
+
+    let reply = conn
+        .get_property(true, window, property, AtomEnum::ANY, 0, u32::MAX / 4)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?;
+    // text big enough to need INCR isn't something to paste in a snippet anyway
+    if reply.type_ == incr {
+        return Err(x11_error("the clipboard text is too big"));
+    }
+
+    Ok(String::from_utf8_lossy(&reply.value).into_owned())
+}
+
+fn create_x11_window(conn: &impl Connection, root: Window) -> Result<Window> {
+    let window = conn.generate_id().map_err(x11_error)?;
+    conn.create_window(
+        0,
+        window,
+        root,
+        0,
+        0,
+        1,
+        1,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        0,
+        &CreateWindowAux::new(),
+    )
+    .map_err(x11_error)?;
+    Ok(window)
+}
+
+// wl-clipboard-rs forks a process that serves the content until something else is copied
+fn copy_wayland(data: &ClipboardData) -> Result<()> {
+    let sources = data
+        .offers
+        .iter()
+        .map(|(mime_type, content)| MimeSource {
+            source: Source::Bytes(content.clone().into_boxed_slice()),
+            // offers the other plain text types too, like UTF8_STRING
+            mime_type: if mime_type == TEXT_MIME_TYPE {
+                MimeType::Text
+            } else {
+                MimeType::Specific(mime_type.clone())
+            },
+        })
+        .collect();
+
+    Options::new().copy_multi(sources).map_err(ClipboardError::Wayland)
+}
+
+// X11 selections are served by their owner, this same binary is started again to
+// be it, in its own process group so closing a terminal doesn't take it down
+fn spawn_x11_server(data: &ClipboardData) -> Result<()> {
+    let mut child = Command::new(env::current_exe()?)
+        .arg(X11_SERVE_FLAG)
+        .stdin(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    let result = match child.stdin.take() {
+        Some(mut stdin) => data.write_to(&mut stdin),
+        None => Ok(()),
+    };
+    // reaped in the background, it only exits when something else is copied
+    thread::spawn(move || child.wait());
+
+    Ok(result?)
+}
+
+/// Entry point of the X11_SERVE_FLAG process, owns the clipboard with the
+/// content read from stdin until another app takes it
+pub fn serve_x11_from_stdin() -> Result<()> {
+    let data = ClipboardData::read_from(&mut BufReader::new(io::stdin().lock()))?;
+    serve_x11(&data)
+}
+
+// a transfer too big for a single property, sent a chunk at a time each time
+// the requestor deletes the property after reading the previous one
+struct IncrTransfer<'a> {
+    requestor: Window,
+    property: Atom,
+    target: Atom,
+    content: &'a [u8],
+    offset: usize,
+}
+
+fn serve_x11(data: &ClipboardData) -> Result<()> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(x11_error)?;
+    let root = conn.setup().roots[screen_num].root;
+    let intern = |name: &str| -> Result<Atom> {
+        Ok(conn
+            .intern_atom(false, name.as_bytes())
+            .map_err(x11_error)?
+            .reply()
+            .map_err(x11_error)?
+            .atom)
+    };
+
+    let clipboard = intern("CLIPBOARD")?;
+    let targets = intern("TARGETS")?;
+    let incr = intern("INCR")?;
+
+    // atom -> content, plain text answers to its X11 specific names as well
+    let mut offers: Vec<(Atom, &[u8])> = Vec::new();
+    for (mime_type, content) in &data.offers {
+        offers.push((intern(mime_type)?, content));
+    }
+    if let Some(text) = data.get(TEXT_MIME_TYPE) {
+        for name in X11_TEXT_TARGETS {
+            offers.push((intern(name)?, text));
+        }
+    }
+    let mut target_list = vec![targets];
+    target_list.extend(offers.iter().map(|(atom, _)| *atom));
+
+    let window = create_x11_window(&conn, root)?;
+    conn.set_selection_owner(window, clipboard, CURRENT_TIME)
+        .map_err(x11_error)?;
+    let owner = conn
+        .get_selection_owner(clipboard)
+        .map_err(x11_error)?
+        .reply()
+        .map_err(x11_error)?
+        .owner;
+    if owner != window {
+        return Err(x11_error("unable to take ownership of the clipboard"));
+    }
+
+    let chunk_size = X11_MAX_CHUNK_SIZE.min(conn.maximum_request_bytes() / 2);
+    let mut transfers: Vec<IncrTransfer> = Vec::new();
+    loop {
+        match conn.wait_for_event().map_err(x11_error)? {
+            Event::SelectionClear(e) if e.selection == clipboard => return Ok(()),
+            Event::SelectionRequest(e) => {
+                // obsolete clients don't say where they want it, the target is used then
+                let property = if e.property == NONE { e.target } else { e.property };
+                let content = offers
+                    .iter()
+                    .find(|(atom, _)| *atom == e.target)
+                    .map(|(_, content)| *content);
+
+                let replied = if e.target == targets {
+                    conn.change_property32(
+                        PropMode::REPLACE,
+                        e.requestor,
+                        property,
+                        AtomEnum::ATOM,
+                        &target_list,
+                    )
+                    .map_err(x11_error)?;
+                    true
+                } else if let Some(content) = content {
+                    if content.len() > chunk_size {
+                        conn.change_window_attributes(
+                            e.requestor,
+                            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+                        )
+                        .map_err(x11_error)?;
+                        conn.change_property32(
+                            PropMode::REPLACE,
+                            e.requestor,
+                            property,
+                            incr,
+                            &[content.len() as u32],
+                        )
+                        .map_err(x11_error)?;
+                        transfers.push(IncrTransfer {
+                            requestor: e.requestor,
+                            property,
+                            target: e.target,
+                            content,
+                            offset: 0,
+                        });
+                    } else {
+                        conn.change_property8(
+                            PropMode::REPLACE,
+                            e.requestor,
+                            property,
+                            e.target,
+                            content,
+                        )
+                        .map_err(x11_error)?;
+                    }
+                    true
+                } else {
+                    false
+                };
+
+                let notify = SelectionNotifyEvent {
+                    response_type: SELECTION_NOTIFY_EVENT,
+                    sequence: 0,
+                    time: e.time,
+                    requestor: e.requestor,
+                    selection: e.selection,
+                    target: e.target,
+                    property: if replied { property } else { NONE },
+                };
+                conn.send_event(false, e.requestor, EventMask::NO_EVENT, notify)
+                    .map_err(x11_error)?;
+                conn.flush().map_err(x11_error)?;
+            }
+            Event::PropertyNotify(e) if e.state == Property::DELETE => {
+                let index = match transfers
+                    .iter()
+                    .position(|it| it.requestor == e.window && it.property == e.atom)
+                {
+                    Some(index) => index,
+                    None => continue,
+                };
+
+                // an empty chunk tells the requestor the transfer is over
+                let transfer = &mut transfers[index];
+                let end = (transfer.offset + chunk_size).min(transfer.content.len());
+                let chunk = &transfer.content[transfer.offset..end];
+                conn.change_property8(
+                    PropMode::REPLACE,
+                    transfer.requestor,
+                    transfer.property,
+                    transfer.target,
+                    chunk,
+                )
+                .map_err(x11_error)?;
+                transfer.offset = end;
+                if chunk.is_empty() {
+                    transfers.remove(index);
+                }
+                conn.flush().map_err(x11_error)?;
+            }
+            _ => (),
+        }
+    }
+}
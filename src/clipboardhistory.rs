@@ -0,0 +1,422 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    os::unix::fs::{DirBuilderExt, OpenOptionsExt},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+use inotify::{Inotify, WatchMask};
+use wl_clipboard_rs::{
+    paste::{self, Seat},
+    watch::{ClipboardEvent, ClipboardType, Watcher},
+};
+
+use crate::{clipboard::PASSWORD_MANAGER_HINT, config, urlutil};
+
+const HISTORY_DIR_NAME: &str = "clipboard";
+const INDEX_FILE_NAME: &str = "index";
+const INOTIFY_BUFFER_SIZE: usize = 4096;
+
+// anything bigger isn't stored at all
+const MAX_ENTRY_SIZE: u64 = 10 * 1024 * 1024;
+// oldest entries are dropped once all of them together go over this
+const MAX_TOTAL_SIZE: u64 = 100 * 1024 * 1024;
+const PREVIEW_MAX_CHARS: usize = 80;
+// only the start of copied text is loaded to show and search, copying it reads the whole file
+const MAX_LOADED_TEXT_SIZE: u64 = 16 * 1024;
+
+const FILE_LIST_MIME_TYPE: &str = "text/uri-list";
+// in order of preference
+const TEXT_MIME_TYPES: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "STRING",
+    "TEXT",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipKind {
+    Text,
+    Image,
+    Files,
+}
+
+impl ClipKind {
+    fn id(&self) -> &'static str {
+        match self {
+            ClipKind::Text => "text",
+            ClipKind::Image => "image",
+            ClipKind::Files => "files",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "text" => Some(ClipKind::Text),
+            "image" => Some(ClipKind::Image),
+            "files" => Some(ClipKind::Files),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClipEntry {
+    pub kind: ClipKind,
+    pub mime_type: String,
+    // blake3 of the content, also the name of the file it's stored in
+    pub hash: String,
+    pub size: u64,
+    pub time: DateTime<Utc>,
+    // copied by selecting it instead of explicitly
+    pub primary: bool,
+    // the start of text and the whole file list, loaded with the index
+    text: Option<String>,
+    // lowercase, what the query is looked for in
+    search_text: String,
+}
+
+impl ClipEntry {
+    pub fn path(&self) -> PathBuf {
+        history_dir().join(&self.hash)
+    }
+
+    pub fn files(&self) -> Vec<String> {
+        match (self.kind, &self.text) {
+            (ClipKind::Files, Some(text)) => text
+                .lines()
+                .filter(|it| !it.starts_with('#'))
+                .filter_map(urlutil::file_url_path)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// One line describing the content, the text itself when it's text
+    pub fn preview(&self) -> String {
+        match self.kind {
+            ClipKind::Text => {
+                let text = self.text.as_deref().unwrap_or_default();
+                let line = text.trim().lines().next().unwrap_or_default();
+                let mut preview: String = line.chars().take(PREVIEW_MAX_CHARS).collect();
+                if line.chars().count() > PREVIEW_MAX_CHARS || text.trim().lines().count() > 1 {
+                    preview.push('…');
+                }
+                preview
+            }
+            ClipKind::Image => format!("Image ({})", self.mime_type),
+            ClipKind::Files => {
+                let names: Vec<String> = self
+                    .files()
+                    .iter()
+                    .filter_map(|it| Path::new(it).file_name())
+                    .map(|it| it.to_string_lossy().to_string())
+                    .collect();
+                names.join(", ")
+            }
+        }
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        self.search_text.contains(query)
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.time.to_rfc3339(),
+            self.kind.id(),
+            self.mime_type,
+            self.hash,
+            self.size,
+            if self.primary { 1 } else { 0 },
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let time = DateTime::parse_from_rfc3339(fields.next()?)
+            .ok()?
+            .with_timezone(&Utc);
+        let kind = ClipKind::from_id(fields.next()?)?;
+        let mime_type = fields.next()?.to_string();
+        let hash = fields.next()?.to_string();
+        let size = fields.next()?.parse().ok()?;
+        let primary = fields.next()? == "1";
+
+        Some(Self {
+            kind,
+            search_text: mime_type.to_lowercase(),
+            mime_type,
+            hash,
+            size,
+            time,
+            primary,
+            text: None,
+        })
+    }
+}
+
+/// What was copied recently, most recent first.
+///
+/// Entries are recorded by `automata --clipboard-daemon`, which has to keep running in
+/// the background, the launcher only reads them and reloads when the daemon changes them.
+/// Everything is kept in `$XDG_DATA_HOME/automata/clipboard`, readable only by the user.
+#[derive(Debug)]
+pub struct ClipboardHistory {
+    entries: Arc<RwLock<Vec<ClipEntry>>>,
+}
+
+impl ClipboardHistory {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        let entries = Arc::new(RwLock::new(vec![]));
+
+        let entries_clone = entries.clone();
+        thread::spawn(move || {
+            let dir = history_dir();
+            let reload = || {
+                *entries_clone.write().expect("ClipboardHistory poisoned") = load(&dir, true);
+                updated.store(true, Ordering::Relaxed);
+            };
+            reload();
+
+            if let Err(e) = create_history_dir(&dir) {
+                println!("unable to create {}: {}", dir.to_string_lossy(), e);
+                return;
+            }
+            let mut inotify = match Inotify::init() {
+                Ok(inotify) => inotify,
+                Err(e) => {
+                    println!("unable to watch clipboard history for changes: {}", e);
+                    return;
+                }
+            };
+            // the index is always replaced, never written in place
+            if let Err(e) = inotify.watches().add(&dir, WatchMask::MOVED_TO) {
+                println!("unable to watch {}: {}", dir.to_string_lossy(), e);
+                return;
+            }
+
+            let mut buffer = [0; INOTIFY_BUFFER_SIZE];
+            while let Ok(events) = inotify.read_events_blocking(&mut buffer) {
+                let changed = events
+                    .into_iter()
+                    .any(|e| e.name.is_some_and(|name| name == INDEX_FILE_NAME));
+                if changed {
+                    reload();
+                }
+            }
+        });
+
+        Self { entries }
+    }
+
+    /// Entries with the query in their text (or file list), all of them when it's empty
+    pub fn matching(&self, query: &str, limit: usize) -> Vec<ClipEntry> {
+        let query = query.trim().to_lowercase();
+        self.entries
+            .read()
+            .expect("ClipboardHistory poisoned")
+            .iter()
+            .filter(|entry| entry.matches(&query))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Records everything copied to the clipboard (and the primary selection, when
+/// `primary` is set) until the Wayland connection goes away.
+///
+/// The compositor has to support the wlr or ext data control protocol, which all
+/// wlroots based ones and KDE do, but GNOME doesn't.
+pub fn run_daemon(max_entries: usize, primary: bool) -> Result<(), paste::Error> {
+    let dir = history_dir();
+    if let Err(e) = create_history_dir(&dir) {
+        println!("unable to create {}: {}", dir.to_string_lossy(), e);
+        return Ok(());
+    }
+
+    let mut watcher = match Watcher::new(
+        if primary { ClipboardType::Both } else { ClipboardType::Regular },
+        Seat::Unspecified,
+    ) {
+        Err(paste::Error::PrimarySelectionUnsupported) => {
+            println!("the compositor has no primary selection, watching only the clipboard");
+            Watcher::new(ClipboardType::Regular, Seat::Unspecified)?
+        }
+        watcher => watcher?,
+    };
+
+    while let Some(event) = watcher.next_event()? {
+        let (clipboard, mime_types, mut offer) = match event {
+            ClipboardEvent::Changed {
+                clipboard,
+                mime_types,
+                offer,
+            } => (clipboard, mime_types, offer),
+            ClipboardEvent::Cleared { .. } => continue,
+        };
+        if mime_types.iter().any(|it| it == PASSWORD_MANAGER_HINT) {
+            continue;
+        }
+
+        let is_primary = clipboard == paste::ClipboardType::Primary;
+        let (kind, mime_type) = match pick_mime_type(&mime_types) {
+            // selecting text is the only way to set the primary selection that makes sense to record
+            Some((kind, _)) if is_primary && kind != ClipKind::Text => continue,
+            Some(picked) => picked,
+            None => continue,
+        };
+
+        let mut content = Vec::new();
+        let read = offer
+            .receive(&mime_type)
+            .map_err(|e| e.to_string())
+            .and_then(|reader| {
+                reader
+                    .take(MAX_ENTRY_SIZE + 1)
+                    .read_to_end(&mut content)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = read {
+            println!("unable to read the clipboard as {}: {}", mime_type, e);
+            continue;
+        }
+        if content.len() as u64 > MAX_ENTRY_SIZE || content.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        if let Err(e) = add(&dir, kind, &mime_type, is_primary, &content, max_entries) {
+            println!("unable to save clipboard entry: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn pick_mime_type(mime_types: &[String]) -> Option<(ClipKind, String)> {
+    if mime_types.iter().any(|it| it == FILE_LIST_MIME_TYPE) {
+        return Some((ClipKind::Files, FILE_LIST_MIME_TYPE.to_owned()));
+    }
+    if let Some(mime_type) = mime_types.iter().find(|it| it.starts_with("image/")) {
+        return Some((ClipKind::Image, mime_type.clone()));
+    }
+
+    TEXT_MIME_TYPES
+        .iter()
+        .find(|it| mime_types.iter().any(|mime_type| mime_type == *it))
+        .map(|it| (ClipKind::Text, it.to_string()))
+}
+
+fn history_dir() -> PathBuf {
+    config::data_dir().join(HISTORY_DIR_NAME)
+}
+
+// copied passwords that slip through and the like end up here, so only the user can read it
+fn create_history_dir(dir: &Path) -> io::Result<()> {
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+}
+
+fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(content)
+}
+
+fn load(dir: &Path, with_text: bool) -> Vec<ClipEntry> {
+    let index = match fs::read_to_string(dir.join(INDEX_FILE_NAME)) {
+        Ok(index) => index,
+        Err(_) => return vec![],
+    };
+
+    index
+        .lines()
+        .filter_map(ClipEntry::from_line)
+        .filter_map(|mut entry| {
+            if with_text && entry.kind != ClipKind::Image {
+                let text = read_text(&dir.join(&entry.hash), entry.kind).ok()?;
+                entry.search_text = text.to_lowercase();
+                entry.text = Some(text);
+            }
+            Some(entry)
+        })
+        .collect()
+}
+
+// file lists are loaded whole since copying them again uses the list
+fn read_text(path: &Path, kind: ClipKind) -> io::Result<String> {
+    let limit = match kind {
+        ClipKind::Text => MAX_LOADED_TEXT_SIZE,
+        _ => MAX_ENTRY_SIZE,
+    };
+    let mut content = Vec::new();
+    fs::File::open(path)?.take(limit).read_to_end(&mut content)?;
+
+    let text = String::from_utf8_lossy(&content);
+    // a character cut in half at the limit isn't worth showing
+    if content.len() as u64 == limit {
+        return Ok(text.trim_end_matches(char::REPLACEMENT_CHARACTER).to_string());
+    }
+    Ok(text.into_owned())
+}
+
+// copying something again moves it to the top instead of adding it twice
+fn add(
+    dir: &Path,
+    kind: ClipKind,
+    mime_type: &str,
+    primary: bool,
+    content: &[u8],
+    max_entries: usize,
+) -> io::Result<()> {
+    let hash = blake3::hash(content).to_hex().to_string();
+    let blob_path = dir.join(&hash);
+    if !blob_path.exists() {
+        write_private(&blob_path, content)?;
+    }
+
+    let mut entries = load(dir, false);
+    entries.retain(|it| it.hash != hash);
+    entries.insert(
+        0,
+        ClipEntry {
+            kind,
+            mime_type: mime_type.to_string(),
+            hash,
+            size: content.len() as u64,
+            time: Utc::now(),
+            primary,
+            text: None,
+            search_text: String::new(),
+        },
+    );
+
+    let mut total_size = 0;
+    let mut kept = Vec::new();
+    for entry in entries {
+        total_size += entry.size;
+        if kept.len() < max_entries && total_size <= MAX_TOTAL_SIZE {
+            kept.push(entry);
+        } else {
+            let _ = fs::remove_file(dir.join(&entry.hash));
+        }
+    }
+
+    // written to a temporary file first, the launcher reloads when it's moved over the index
+    let index: String = kept.iter().map(|it| format!("{}\n", it.to_line())).collect();
+    let tmp_path = dir.join(format!("{}.tmp", INDEX_FILE_NAME));
+    write_private(&tmp_path, index.as_bytes())?;
+    fs::rename(tmp_path, dir.join(INDEX_FILE_NAME))
+}
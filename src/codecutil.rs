@@ -0,0 +1,305 @@
+use std::sync::LazyLock;
+
+use base64::prelude::*;
+use chrono::{DateTime, Local, Utc};
+use percent_encoding::{NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use regex::Regex;
+use serde_json::Value;
+
+// shortest input that auto-detection will try to interpret as base64/hex,
+// anything shorter is too likely to be a normal word
+const MIN_AUTODETECT_LEN: usize = 8;
+// "price=20" or "a=10 b=20" look like escapes too, encoded text has more of them
+const MIN_QUOTED_PRINTABLE_ESCAPES: usize = 3;
+
+const JWT_TIME_CLAIMS: [&str; 3] = ["exp", "iat", "nbf"];
+
+// detection runs on every key press, so these are only compiled once
+static PERCENT_ESCAPE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"%[0-9A-Fa-f]{2}").unwrap());
+static HTML_ENTITY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9A-Fa-f]+|[a-zA-Z]+);").unwrap());
+static QUOTED_PRINTABLE_BREAK_OR_PAIR_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"=\r?\n|(=[0-9A-F]{2}){2}").unwrap());
+static QUOTED_PRINTABLE_ESCAPE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"=[0-9A-F]{2}").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+    Base64,
+    Base64Url,
+    Url,
+    Hex,
+    Html,
+    QuotedPrintable,
+    Jwt,
+}
+
+#[derive(Debug)]
+pub enum CodecError {
+    InvalidInput(Codec),
+    NotUtf8(Codec),
+    Unsupported(Codec),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::InvalidInput(codec) => write!(f, "Invalid {} input", codec.name()),
+            CodecError::NotUtf8(codec) => write!(f, "Decoded {} is not valid UTF-8", codec.name()),
+            CodecError::Unsupported(codec) => {
+                write!(f, "Operation not supported for {}", codec.name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub type Result<T> = std::result::Result<T, CodecError>;
+
+#[derive(Debug, Clone)]
+pub struct JwtClaimTime {
+    pub claim: String,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedJwt {
+    pub header: String,
+    pub payload: String,
+    pub times: Vec<JwtClaimTime>,
+}
+
+impl Codec {
+    pub const ALL: [Codec; 7] = [
+        Codec::Jwt,
+        Codec::Base64,
+        Codec::Base64Url,
+        Codec::Url,
+        Codec::Hex,
+        Codec::Html,
+        Codec::QuotedPrintable,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Codec::Base64 => "Base64",
+            Codec::Base64Url => "Base64URL",
+            Codec::Url => "URL",
+            Codec::Hex => "Hex",
+            Codec::Html => "HTML",
+            Codec::QuotedPrintable => "Quoted-printable",
+            Codec::Jwt => "JWT",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Codec::Base64 => "b64",
+            Codec::Base64Url => "b64url",
+            Codec::Url => "url",
+            Codec::Hex => "hex",
+            Codec::Html => "html",
+            Codec::QuotedPrintable => "qp",
+            Codec::Jwt => "jwt",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Codec> {
+        match prefix.to_lowercase().as_str() {
+            "b64" | "base64" => Some(Codec::Base64),
+            "b64url" | "base64url" => Some(Codec::Base64Url),
+            "url" | "urlencode" | "percent" => Some(Codec::Url),
+            "hex" => Some(Codec::Hex),
+            "html" => Some(Codec::Html),
+            "qp" | "quoted-printable" => Some(Codec::QuotedPrintable),
+            "jwt" => Some(Codec::Jwt),
+            _ => None,
+        }
+    }
+}
+
+/// Splits inputs like "b64 some text" into the codec and the text to operate on
+pub fn parse_prefix(input: &str) -> Option<(Codec, &str)> {
+    let (prefix, rest) = input.split_once(' ')?;
+    let codec = Codec::from_prefix(prefix)?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    Some((codec, rest))
+}
+
+pub fn encode(codec: Codec, input: &str) -> Result<String> {
+    match codec {
+        Codec::Base64 => Ok(BASE64_STANDARD.encode(input)),
+        Codec::Base64Url => Ok(BASE64_URL_SAFE_NO_PAD.encode(input)),
+        Codec::Url => Ok(utf8_percent_encode(input, NON_ALPHANUMERIC).to_string()),
+        Codec::Hex => Ok(hex::encode(input)),
+        Codec::Html => Ok(html_escape::encode_safe(input).into_owned()),
+        Codec::QuotedPrintable => Ok(quoted_printable::encode_to_str(input)),
+        Codec::Jwt => Err(CodecError::Unsupported(codec)),
+    }
+}
+
+pub fn decode(codec: Codec, input: &str) -> Result<String> {
+    let bytes = match codec {
+        Codec::Base64 => BASE64_STANDARD
+            .decode(input)
+            .map_err(|_| CodecError::InvalidInput(codec))?,
+        Codec::Base64Url => decode_b64url(input).ok_or(CodecError::InvalidInput(codec))?,
+        Codec::Url => percent_decode_str(input).collect(),
+        Codec::Hex => hex::decode(input).map_err(|_| CodecError::InvalidInput(codec))?,
+        Codec::Html => html_escape::decode_html_entities(input).into_owned().into_bytes(),
+        Codec::QuotedPrintable => {
+            quoted_printable::decode(input, quoted_printable::ParseMode::Strict)
+                .map_err(|_| CodecError::InvalidInput(codec))?
+        }
+        Codec::Jwt => return Err(CodecError::Unsupported(codec)),
+    };
+
+    String::from_utf8(bytes).map_err(|_| CodecError::NotUtf8(codec))
+}
+
+pub fn decode_jwt(input: &str) -> Result<DecodedJwt> {
+    let parts: Vec<&str> = input.trim().split('.').collect();
+    if parts.len() != 3 {
+        return Err(CodecError::InvalidInput(Codec::Jwt));
+    }
+
+    let header = decode_jwt_segment(parts[0])?;
+    let payload = decode_jwt_segment(parts[1])?;
+
+    let times = JWT_TIME_CLAIMS
+        .iter()
+        .filter_map(|claim| {
+            let secs = payload.get(claim)?.as_i64()?;
+            let time = DateTime::from_timestamp(secs, 0)?;
+            Some(JwtClaimTime {
+                claim: claim.to_string(),
+                time,
+            })
+        })
+        .collect();
+
+    Ok(DecodedJwt {
+        header: serde_json::to_string_pretty(&header).unwrap_or_default(),
+        payload: serde_json::to_string_pretty(&payload).unwrap_or_default(),
+        times,
+    })
+}
+
+pub fn format_claim_time(t: &JwtClaimTime) -> String {
+    let local = t.time.with_timezone(&Local);
+    let relation = if t.time < Utc::now() { "past" } else { "future" };
+    format!(
+        "{}: {} ({}, {})",
+        t.claim,
+        local.format("%Y-%m-%d %H:%M:%S %Z"),
+        t.time.format("%Y-%m-%d %H:%M:%S UTC"),
+        relation
+    )
+}
+
+/// Lists the codecs that `input` looks to be encoded with.
+///
+/// This is intentionally conservative: a decode is only suggested when the input has
+/// characteristics of the encoding and the result is readable text, so ordinary words
+/// that happen to be valid base64/hex don't flood the suggestions.
+pub fn detect(input: &str) -> Vec<Codec> {
+    let input = input.trim();
+    if input.is_empty() {
+        return vec![];
+    }
+
+    Codec::ALL
+        .into_iter()
+        .filter(|codec| match codec {
+            Codec::Jwt => decode_jwt(input).is_ok(),
+            Codec::Base64 => looks_base64(input, false) && decodes_to_text(*codec, input),
+            Codec::Base64Url => {
+                looks_base64(input, true)
+                    && !looks_base64(input, false)
+                    && decodes_to_text(*codec, input)
+            }
+            Codec::Url => looks_percent_encoded(input) && decodes_to_text(*codec, input),
+            Codec::Hex => looks_hex(input) && decodes_to_text(*codec, input),
+            Codec::Html => looks_html_escaped(input) && decodes_to_text(*codec, input),
+            Codec::QuotedPrintable => {
+                looks_quoted_printable(input)
+                    && !looks_percent_encoded(input)
+                    && decodes_to_text(*codec, input)
+            }
+        })
+        .collect()
+}
+
+fn decode_b64url(input: &str) -> Option<Vec<u8>> {
+    BASE64_URL_SAFE_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .ok()
+}
+
+fn decode_jwt_segment(segment: &str) -> Result<Value> {
+    let bytes = decode_b64url(segment).ok_or(CodecError::InvalidInput(Codec::Jwt))?;
+    let value: Value =
+        serde_json::from_slice(&bytes).map_err(|_| CodecError::InvalidInput(Codec::Jwt))?;
+    if !value.is_object() {
+        return Err(CodecError::InvalidInput(Codec::Jwt));
+    }
+
+    Ok(value)
+}
+
+fn decodes_to_text(codec: Codec, input: &str) -> bool {
+    match decode(codec, input) {
+        Ok(s) => s != input && is_readable(&s),
+        Err(_) => false,
+    }
+}
+
+fn is_readable(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+}
+
+fn looks_base64(input: &str, url_safe: bool) -> bool {
+    if input.len() < MIN_AUTODETECT_LEN {
+        return false;
+    }
+
+    let (extra_a, extra_b) = if url_safe { ('-', '_') } else { ('+', '/') };
+    let valid_chars = input
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == extra_a || c == extra_b || c == '=');
+    if !valid_chars || (!url_safe && !input.len().is_multiple_of(4)) {
+        return false;
+    }
+
+    // plain words are only letters, real payloads nearly always mix in
+    // digits, symbols, padding or uppercase letters after the first one
+    input
+        .chars()
+        .skip(1)
+        .any(|c| !c.is_ascii_lowercase())
+}
+
+fn looks_hex(input: &str) -> bool {
+    input.len() >= MIN_AUTODETECT_LEN
+        && input.len().is_multiple_of(2)
+        && input.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn looks_percent_encoded(input: &str) -> bool {
+    PERCENT_ESCAPE_REGEX.is_match(input)
+}
+
+fn looks_html_escaped(input: &str) -> bool {
+    HTML_ENTITY_REGEX.is_match(input)
+}
+
+// a soft line break, a multibyte character ("=C3=A9") or several escapes
+fn looks_quoted_printable(input: &str) -> bool {
+    QUOTED_PRINTABLE_BREAK_OR_PAIR_REGEX.is_match(input)
+        || QUOTED_PRINTABLE_ESCAPE_REGEX.find_iter(input).count() >= MIN_QUOTED_PRINTABLE_ESCAPES
+}
@@ -0,0 +1,34 @@
+use std::cell::RefCell;
+
+use gtk4::{glib, subclass::prelude::*};
+
+#[derive(Debug, Default, gtk4::CompositeTemplate)]
+#[template(file = "template.ui")]
+pub struct ActionPanel {
+    #[template_child]
+    pub title: TemplateChild<gtk4::Label>,
+    #[template_child]
+    pub list: TemplateChild<gtk4::ListBox>,
+
+    // the suggestion whose actions are being shown
+    pub suggestion_id: RefCell<Option<String>>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for ActionPanel {
+    const NAME: &'static str = "ActionPanel";
+    type Type = super::ActionPanel;
+    type ParentType = gtk4::Box;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for ActionPanel {}
+impl WidgetImpl for ActionPanel {}
+impl BoxImpl for ActionPanel {}
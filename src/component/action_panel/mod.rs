@@ -0,0 +1,88 @@
+mod imp;
+use gtk4::{glib, prelude::*, subclass::prelude::*};
+
+use crate::suggestions::Suggestion;
+
+// the keys that run each action directly, by their position in the list
+pub const ACTION_SHORTCUTS: [&str; 4] = ["Enter", "Shift+Enter", "Ctrl+Enter", "Alt+Enter"];
+
+glib::wrapper! {
+    pub struct ActionPanel(ObjectSubclass<imp::ActionPanel>)
+        @extends gtk4::Widget, gtk4::Box,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Orientable;
+}
+
+impl Default for ActionPanel {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl ActionPanel {
+    /// Lists every action of the suggestion with the shortcut that runs it, if any
+    pub fn show_actions(&self, suggestion: &Suggestion) {
+        let imp = self.imp();
+        imp.title.set_text(&format!("Actions for '{}'", suggestion.title));
+        while let Some(child) = imp.list.first_child() {
+            imp.list.remove(&child);
+        }
+
+        for (i, action) in suggestion.actions.iter().enumerate() {
+            let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+            row.set_margin_top(6);
+            row.set_margin_bottom(6);
+            row.set_margin_start(6);
+            row.set_margin_end(6);
+
+            let label = gtk4::Label::new(Some(&action.label));
+            label.set_halign(gtk4::Align::Start);
+            label.set_hexpand(true);
+            row.append(&label);
+
+            if let Some(shortcut) = ACTION_SHORTCUTS.get(i) {
+                let hint = gtk4::Label::new(Some(shortcut));
+                hint.add_css_class("dim-label");
+                row.append(&hint);
+            }
+
+            imp.list.append(&row);
+        }
+
+        imp.suggestion_id.replace(Some(suggestion.id.clone()));
+        imp.list.select_row(imp.list.row_at_index(0).as_ref());
+        self.set_visible(true);
+    }
+
+    pub fn hide(&self) {
+        self.imp().suggestion_id.replace(None);
+        self.set_visible(false);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_visible()
+    }
+
+    pub fn select_next(&self) {
+        self.move_selection(1);
+    }
+
+    pub fn select_previous(&self) {
+        self.move_selection(-1);
+    }
+
+    fn move_selection(&self, offset: i32) {
+        let list = &self.imp().list;
+        let current = list.selected_row().map(|it| it.index()).unwrap_or(0);
+        if let Some(row) = list.row_at_index(current + offset) {
+            list.select_row(Some(&row));
+        }
+    }
+
+    /// The suggestion id and the index of the selected action in it
+    pub fn selected_action(&self) -> Option<(String, usize)> {
+        let imp = self.imp();
+        let id = imp.suggestion_id.borrow().clone()?;
+        let index = imp.list.selected_row()?.index();
+        Some((id, index as usize))
+    }
+}
@@ -1 +1,3 @@
+pub mod action_panel;
+pub mod output_panel;
 pub mod suggestion_row;
@@ -0,0 +1,68 @@
+use std::cell::{Cell, RefCell};
+
+use gtk4::{glib, prelude::*, subclass::prelude::*};
+
+use crate::sysaction::{self, CapturedProcess};
+
+#[derive(Debug, Default, gtk4::CompositeTemplate)]
+#[template(file = "template.ui")]
+pub struct OutputPanel {
+    #[template_child]
+    pub status: TemplateChild<gtk4::Label>,
+    #[template_child]
+    pub copy_button: TemplateChild<gtk4::Button>,
+    #[template_child]
+    pub kill_button: TemplateChild<gtk4::Button>,
+    #[template_child]
+    pub scrollable: TemplateChild<gtk4::ScrolledWindow>,
+    #[template_child]
+    pub output_view: TemplateChild<gtk4::TextView>,
+
+    pub process: RefCell<Option<CapturedProcess>>,
+    // bumped for every new process, so the timer of a previous one knows to stop
+    pub generation: Cell<u64>,
+    // bytes of output already added to the view
+    pub received: Cell<usize>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for OutputPanel {
+    const NAME: &'static str = "OutputPanel";
+    type Type = super::OutputPanel;
+    type ParentType = gtk4::Box;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for OutputPanel {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        let obj = self.obj().downgrade();
+        self.kill_button.connect_clicked(move |_| {
+            if let Some(panel) = obj.upgrade()
+                && let Some(process) = panel.imp().process.borrow().as_ref()
+            {
+                process.kill();
+            }
+        });
+
+        let obj = self.obj().downgrade();
+        self.copy_button.connect_clicked(move |_| {
+            if let Some(panel) = obj.upgrade()
+                && let Some(process) = panel.imp().process.borrow().as_ref()
+            {
+                sysaction::set_clipboard(&process.output());
+            }
+        });
+    }
+}
+
+impl WidgetImpl for OutputPanel {}
+impl BoxImpl for OutputPanel {}
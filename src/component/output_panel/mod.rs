@@ -0,0 +1,104 @@
+mod imp;
+use std::{os::unix::process::ExitStatusExt, time::Duration};
+
+use gtk4::{glib, prelude::*, subclass::prelude::*};
+
+use crate::sysaction::{CapturedProcess, MAX_CAPTURED_OUTPUT_SIZE};
+
+const OUTPUT_REFRESH_INTERVAL_MS: u64 = 100;
+// the view keeps about as much as the process does, every character is at least a byte
+const MAX_SHOWN_CHARS: i32 = MAX_CAPTURED_OUTPUT_SIZE as i32;
+
+glib::wrapper! {
+    pub struct OutputPanel(ObjectSubclass<imp::OutputPanel>)
+        @extends gtk4::Widget, gtk4::Box,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Orientable;
+}
+
+impl Default for OutputPanel {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl OutputPanel {
+    /// Shows the panel and keeps it updated with the process output until it finishes
+    pub fn show_process(&self, process: CapturedProcess) {
+        let imp = self.imp();
+        let generation = imp.generation.get() + 1;
+        imp.generation.set(generation);
+        imp.process.replace(Some(process));
+        imp.received.set(0);
+        imp.output_view.buffer().set_text("");
+        self.set_visible(true);
+        self.update();
+
+        let panel = self.downgrade();
+        glib::timeout_add_local(Duration::from_millis(OUTPUT_REFRESH_INTERVAL_MS), move || {
+            let panel = match panel.upgrade() {
+                Some(panel) => panel,
+                None => return glib::ControlFlow::Break,
+            };
+            if panel.imp().generation.get() != generation {
+                return glib::ControlFlow::Break;
+            }
+
+            if panel.update() {
+                glib::ControlFlow::Break
+            } else {
+                glib::ControlFlow::Continue
+            }
+        });
+    }
+
+    // returns whether the process finished, so there is nothing else to update
+    fn update(&self) -> bool {
+        let imp = self.imp();
+        let process = match imp.process.borrow().clone() {
+            Some(process) => process,
+            None => return true,
+        };
+
+        // checked before reading the output so nothing written right before exiting is missed
+        let finished = process.is_finished();
+
+        // only what arrived since the last update is added to the view
+        let chunk = process.output_after(imp.received.get());
+        if chunk.received != imp.received.get() {
+            imp.received.set(chunk.received);
+            let buffer = imp.output_view.buffer();
+            if chunk.continues {
+                buffer.insert(&mut buffer.end_iter(), &chunk.text);
+            } else {
+                buffer.set_text(&chunk.text);
+            }
+
+            let excess = buffer.char_count() - MAX_SHOWN_CHARS;
+            if excess > 0 {
+                buffer.delete(&mut buffer.start_iter(), &mut buffer.iter_at_offset(excess));
+            }
+
+            let mut end = buffer.end_iter();
+            imp.output_view.scroll_to_iter(&mut end, 0.0, false, 0.0, 0.0);
+        }
+
+        let exit_status = process.try_exit_status();
+        let status = match exit_status {
+            None => "Running…".to_owned(),
+            Some(status) => match (status.code(), status.signal()) {
+                (Some(code), _) => format!("Exited with code {}", code),
+                (None, Some(signal)) => format!("Killed by signal {}", signal),
+                (None, None) => "Exited".to_owned(),
+            },
+        };
+        let status = if process.is_truncated() {
+            format!("{} (earlier output truncated)", status)
+        } else {
+            status
+        };
+        imp.status.set_text(&status);
+        imp.kill_button.set_sensitive(exit_status.is_none());
+
+        finished
+    }
+}
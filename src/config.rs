@@ -0,0 +1,214 @@
+use std::{env, path::PathBuf};
+
+use ini::Ini;
+
+use crate::searchengine::{self, DEFAULT_SEARCH_ENGINE, QUERY_PLACEHOLDER, SearchEngine};
+
+const CONFIG_DIR_NAME: &str = "automata";
+const CONFIG_FILE_NAME: &str = "config.ini";
+
+const TIME_SECTION: &str = "time";
+const SEARCH_SECTION: &str = "search";
+const SEARCH_ENGINE_SECTION_PREFIX: &str = "search.";
+const HISTORY_SECTION: &str = "history";
+const FILES_SECTION: &str = "files";
+const CLIPBOARD_SECTION: &str = "clipboard";
+
+const DEFAULT_CLIPBOARD_MAX_ENTRIES: usize = 200;
+
+/// User configuration, read from `$XDG_CONFIG_HOME/automata/config.ini`.
+///
+/// Every value is optional, missing keys (or a missing file) keep the defaults.
+///
+/// ```ini
+/// [time]
+/// zones = Europe/Berlin, America/New_York
+///
+/// [search]
+/// default = ddg
+/// also = gh, wiki
+///
+/// [search.crates]
+/// name = crates.io
+/// url = https://crates.io/search?q={query}
+///
+/// [history]
+/// files = ~/.zsh_history, ~/.local/share/fish/fish_history
+///
+/// [files]
+/// roots = ~, /mnt/data
+/// exclude = node_modules, target, ~/Downloads/torrents
+///
+/// [clipboard]
+/// max_entries = 500
+/// primary = true
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    // extra timezones to show when converting timestamps
+    pub time_zones: Vec<String>,
+
+    // builtin engines plus the ones defined in `[search.<keyword>]` sections
+    pub search_engines: Vec<SearchEngine>,
+    // keyword of the engine used by the fallback search row
+    pub default_search_engine: String,
+    // keywords of engines also offered for every input, after the default one
+    pub extra_search_engines: Vec<String>,
+
+    // shell history files to suggest commands from, when empty `$HISTFILE`
+    // or the default locations of bash, zsh and fish are used
+    pub history_files: Vec<String>,
+
+    // directories indexed for file search, $HOME when empty
+    pub file_roots: Vec<String>,
+    // directory names, or paths when they contain a `/`, left out of the file index
+    pub file_excludes: Vec<String>,
+
+    // how many entries the clipboard history keeps
+    pub clipboard_max_entries: usize,
+    // whether selected text (the primary selection) is recorded too, off by default
+    // as whatever gets highlighted would end up on disk
+    pub clipboard_primary: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            time_zones: vec![],
+            search_engines: searchengine::builtin_engines(),
+            default_search_engine: DEFAULT_SEARCH_ENGINE.to_owned(),
+            extra_search_engines: vec![],
+            history_files: vec![],
+            file_roots: vec![],
+            file_excludes: vec![],
+            clipboard_max_entries: DEFAULT_CLIPBOARD_MAX_ENTRIES,
+            clipboard_primary: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let path = config_dir().join(CONFIG_FILE_NAME);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match Ini::load_from_file(&path) {
+            Ok(ini) => Self::from_ini(&ini),
+            Err(e) => {
+                println!("unable to load config from {}: {}", path.to_string_lossy(), e);
+                Self::default()
+            }
+        }
+    }
+
+    fn from_ini(ini: &Ini) -> Self {
+        let defaults = Self::default();
+        let mut config = Self {
+            time_zones: get_list(ini, TIME_SECTION, "zones"),
+            search_engines: load_search_engines(ini, defaults.search_engines),
+            default_search_engine: ini
+                .get_from(Some(SEARCH_SECTION), "default")
+                .map(|it| it.trim().to_string())
+                .unwrap_or(defaults.default_search_engine),
+            extra_search_engines: get_list(ini, SEARCH_SECTION, "also"),
+            history_files: get_list(ini, HISTORY_SECTION, "files"),
+            file_roots: get_list(ini, FILES_SECTION, "roots"),
+            file_excludes: get_list(ini, FILES_SECTION, "exclude"),
+            clipboard_max_entries: ini
+                .get_from(Some(CLIPBOARD_SECTION), "max_entries")
+                .and_then(|it| it.trim().parse().ok())
+                .unwrap_or(defaults.clipboard_max_entries),
+            clipboard_primary: ini
+                .get_from(Some(CLIPBOARD_SECTION), "primary")
+                .map(|it| it.trim() == "true")
+                .unwrap_or(defaults.clipboard_primary),
+        };
+
+        // a typo shouldn't take away the web search fallback
+        if config.get_search_engine(&config.default_search_engine).is_none() {
+            println!(
+                "unknown default search engine '{}', using {}",
+                config.default_search_engine, DEFAULT_SEARCH_ENGINE
+            );
+            config.default_search_engine = DEFAULT_SEARCH_ENGINE.to_owned();
+        }
+        for keyword in &config.extra_search_engines {
+            if config.get_search_engine(keyword).is_none() {
+                println!("ignoring unknown search engine '{}'", keyword);
+            }
+        }
+
+        config
+    }
+
+    pub fn get_search_engine(&self, keyword: &str) -> Option<&SearchEngine> {
+        self.search_engines
+            .iter()
+            .find(|engine| engine.keyword.eq_ignore_ascii_case(keyword))
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config").join(CONFIG_DIR_NAME)
+}
+
+/// Where data that can be rebuilt at any time is kept, like the file index
+pub fn cache_dir() -> PathBuf {
+    xdg_dir("XDG_CACHE_HOME", ".cache").join(CONFIG_DIR_NAME)
+}
+
+/// Where data that can't be rebuilt is kept, like the clipboard history
+pub fn data_dir() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", ".local/share").join(CONFIG_DIR_NAME)
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> PathBuf {
+    match env::var(var) {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home_path = env::var("HOME").expect("expected $HOME to always be defined");
+            PathBuf::from(home_path).join(home_fallback)
+        }
+    }
+}
+
+// user defined engines replace builtin ones with the same keyword
+fn load_search_engines(ini: &Ini, mut engines: Vec<SearchEngine>) -> Vec<SearchEngine> {
+    for (section, properties) in ini.iter() {
+        let keyword = match section.and_then(|it| it.strip_prefix(SEARCH_ENGINE_SECTION_PREFIX)) {
+            Some(keyword) if !keyword.is_empty() => keyword,
+            _ => continue,
+        };
+
+        let url_template = match properties.get("url") {
+            Some(url) if url.contains(QUERY_PLACEHOLDER) => url,
+            _ => {
+                println!(
+                    "ignoring search engine '{}', its url should contain {}",
+                    keyword, QUERY_PLACEHOLDER
+                );
+                continue;
+            }
+        };
+        let name = properties.get("name").unwrap_or(keyword);
+
+        engines.retain(|engine| !engine.keyword.eq_ignore_ascii_case(keyword));
+        engines.push(SearchEngine::new(keyword, name, url_template));
+    }
+
+    engines
+}
+
+fn get_list(ini: &Ini, section: &str, key: &str) -> Vec<String> {
+    ini.get_from(Some(section), key)
+        .map(|value| {
+            value
+                .split(',')
+                .map(|it| it.trim().to_string())
+                .filter(|it| !it.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,187 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+
+const MAX_MATCHES: usize = 50;
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+// like ripgrep, a NUL byte in the beginning of a file means it's binary
+const BINARY_CHECK_SIZE: usize = 8 * 1024;
+const SNIPPET_MAX_CHARS: usize = 120;
+
+#[derive(Debug, Clone)]
+pub struct ContentMatch {
+    pub path: PathBuf,
+    // starting from 1, as editors count them
+    pub line: usize,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentSearchState {
+    pub matches: Vec<ContentMatch>,
+    pub done: bool,
+}
+
+#[derive(Debug)]
+struct SearchJob {
+    root: PathBuf,
+    pattern: String,
+    matches: Arc<Mutex<Vec<ContentMatch>>>,
+    done: Arc<AtomicBool>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Searches file contents under a directory in a background thread, so matches show
+/// up while the search is still running. Only one search runs at a time, starting a
+/// new one cancels the previous.
+#[derive(Debug)]
+pub struct ContentSearch {
+    job: Mutex<Option<SearchJob>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl ContentSearch {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        Self {
+            job: Mutex::new(None),
+            updated,
+        }
+    }
+
+    pub fn get_or_start(&self, root: &Path, pattern: &str) -> ContentSearchState {
+        let mut job = self.job.lock().expect("ContentSearch poisoned");
+        if let Some(current) = job.as_ref() {
+            if current.root == root && current.pattern == pattern {
+                return ContentSearchState {
+                    matches: current.matches.lock().expect("ContentSearch poisoned").clone(),
+                    done: current.done.load(Ordering::Relaxed),
+                };
+            }
+
+            current.cancelled.store(true, Ordering::Relaxed);
+        }
+
+        let new_job = SearchJob {
+            root: root.to_path_buf(),
+            pattern: pattern.to_string(),
+            matches: Arc::new(Mutex::new(vec![])),
+            done: Arc::new(AtomicBool::new(false)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+
+        let regex = build_regex(pattern);
+        let root = new_job.root.clone();
+        let matches = new_job.matches.clone();
+        let done = new_job.done.clone();
+        let cancelled = new_job.cancelled.clone();
+        let updated = self.updated.clone();
+        thread::spawn(move || {
+            search(&root, &regex, &matches, &cancelled, &updated);
+            done.store(true, Ordering::Relaxed);
+            updated.store(true, Ordering::Relaxed);
+        });
+
+        *job = Some(new_job);
+        ContentSearchState {
+            matches: vec![],
+            done: false,
+        }
+    }
+}
+
+// the pattern is taken literally, and only matches case sensitively when it has uppercase letters
+fn build_regex(pattern: &str) -> Regex {
+    RegexBuilder::new(&regex::escape(pattern))
+        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+        .build()
+        .expect("escaped patterns should always be valid")
+}
+
+fn search(
+    root: &Path,
+    regex: &Regex,
+    matches: &Mutex<Vec<ContentMatch>>,
+    cancelled: &AtomicBool,
+    updated: &AtomicBool,
+) {
+    let walker = WalkBuilder::new(root).require_git(false).build();
+    for entry in walker.flatten() {
+        if cancelled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let path = entry.path();
+        let is_small_file = entry
+            .metadata()
+            .is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_SIZE);
+        if !is_small_file {
+            continue;
+        }
+
+        let content = match read_text(path) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        for (i, line) in content.lines().enumerate() {
+            let found = match regex.find(line) {
+                Some(found) => found,
+                None => continue,
+            };
+
+            let mut matches = matches.lock().expect("ContentSearch poisoned");
+            matches.push(ContentMatch {
+                path: path.to_path_buf(),
+                line: i + 1,
+                snippet: snippet(line, found.start()),
+            });
+            updated.store(true, Ordering::Relaxed);
+
+            if matches.len() >= MAX_MATCHES {
+                return;
+            }
+        }
+    }
+}
+
+fn read_text(path: &Path) -> Option<String> {
+    let mut bytes = Vec::new();
+    fs::File::open(path).ok()?.read_to_end(&mut bytes).ok()?;
+
+    let head = &bytes[..bytes.len().min(BINARY_CHECK_SIZE)];
+    if head.contains(&0) {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// the matched line, starting a bit before the match when it's too long to show whole
+fn snippet(line: &str, match_start: usize) -> String {
+    let line = line.trim_end();
+    if line.chars().count() <= SNIPPET_MAX_CHARS {
+        return line.trim_start().to_string();
+    }
+
+    let context_start = line[..match_start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_MAX_CHARS / 4)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let rest = &line[context_start..];
+    let snippet: String = rest.chars().take(SNIPPET_MAX_CHARS).collect();
+    let prefix = if context_start > 0 { "…" } else { "" };
+    let suffix = if rest.chars().count() > SNIPPET_MAX_CHARS { "…" } else { "" };
+    format!("{}{}{}", prefix, snippet.trim_start(), suffix)
+}
@@ -0,0 +1,187 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateResult {
+    Date(NaiveDate),
+    // difference between two dates, as (from, to)
+    Duration(NaiveDate, NaiveDate),
+    Week(NaiveDate),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Add,
+    Subtract,
+}
+
+/// Evaluates date expressions like "today + 45 days", "days until 2027-03-01",
+/// "2026-01-05 - 2025-11-30", "next friday" or "week number"
+pub fn evaluate(input: &str) -> Option<DateResult> {
+    evaluate_relative_to(input, Local::now().date_naive())
+}
+
+pub fn evaluate_relative_to(input: &str, today: NaiveDate) -> Option<DateResult> {
+    let input = input.trim().to_lowercase();
+
+    if let Some(rest) = input.strip_prefix("week number") {
+        let rest = rest.trim().trim_start_matches("of ").trim();
+        let date = if rest.is_empty() {
+            today
+        } else {
+            evaluate_date(rest, today)?
+        };
+        return Some(DateResult::Week(date));
+    }
+
+    if let Some(rest) = input.strip_prefix("days until ") {
+        return Some(DateResult::Duration(today, evaluate_date(rest, today)?));
+    }
+
+    if let Some(rest) = input.strip_prefix("days since ") {
+        return Some(DateResult::Duration(evaluate_date(rest, today)?, today));
+    }
+
+    if let Some(rest) = input.strip_prefix("days between ") {
+        let (from, to) = rest.split_once(" and ")?;
+        return Some(DateResult::Duration(
+            evaluate_date(from, today)?,
+            evaluate_date(to, today)?,
+        ));
+    }
+
+    // "<date> - <date>" is the only expression that doesn't result in a date
+    let operands = split_operands(&input);
+    if let [(_, left), (Operation::Subtract, right)] = operands.as_slice()
+        && let (Some(left), Some(right)) =
+            (parse_date_term(left, today), parse_date_term(right, today))
+    {
+        return Some(DateResult::Duration(right, left));
+    }
+
+    evaluate_date(&input, today).map(DateResult::Date)
+}
+
+// a date term followed by any number of "+ N unit" / "- N unit"
+fn evaluate_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let operands = split_operands(input);
+    let (_, first) = operands.first()?;
+    let mut date = parse_date_term(first, today)?;
+
+    for (operation, operand) in operands.iter().skip(1) {
+        date = apply_duration(date, *operation, operand)?;
+    }
+
+    Some(date)
+}
+
+// operators need spaces around them, since dates themselves contain dashes
+fn split_operands(input: &str) -> Vec<(Operation, String)> {
+    let operator_regex = Regex::new(r"\s+([+-])\s+").unwrap();
+    let mut operands = Vec::new();
+    let mut operation = Operation::Add;
+    let mut last_end = 0;
+
+    for captures in operator_regex.captures_iter(input) {
+        let whole = captures.get(0).unwrap();
+        operands.push((operation, input[last_end..whole.start()].trim().to_string()));
+        operation = match &captures[1] {
+            "-" => Operation::Subtract,
+            _ => Operation::Add,
+        };
+        last_end = whole.end();
+    }
+    operands.push((operation, input[last_end..].trim().to_string()));
+
+    operands
+}
+
+fn parse_date_term(term: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match term {
+        "today" | "now" => return Some(today),
+        "tomorrow" => return today.checked_add_days(Days::new(1)),
+        "yesterday" => return today.checked_sub_days(Days::new(1)),
+        _ => (),
+    }
+
+    for format in ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(term, format) {
+            return Some(date);
+        }
+    }
+
+    // a bare weekday is too likely to be part of a normal search
+    let (modifier, weekday_name) = term.split_once(' ')?;
+    let weekday: Weekday = weekday_name.parse().ok()?;
+    let days_ahead = (7 + weekday.num_days_from_monday() as i64
+        - today.weekday().num_days_from_monday() as i64)
+        % 7;
+
+    match modifier {
+        // the next occurrence, never today
+        "next" => today.checked_add_days(Days::new(if days_ahead == 0 { 7 } else { days_ahead } as u64)),
+        // the last occurrence, never today
+        "last" => today.checked_sub_days(Days::new(if days_ahead == 0 { 7 } else { 7 - days_ahead } as u64)),
+        // the upcoming occurrence, including today
+        "this" => today.checked_add_days(Days::new(days_ahead as u64)),
+        _ => None,
+    }
+}
+
+fn apply_duration(date: NaiveDate, operation: Operation, duration: &str) -> Option<NaiveDate> {
+    let duration_regex = Regex::new(r"^(\d+)\s*([a-z]+)$").unwrap();
+    let captures = duration_regex.captures(duration)?;
+    let amount: u32 = captures[1].parse().ok()?;
+
+    match (&captures[2], operation) {
+        ("d" | "day" | "days", Operation::Add) => date.checked_add_days(Days::new(amount as u64)),
+        ("d" | "day" | "days", Operation::Subtract) => date.checked_sub_days(Days::new(amount as u64)),
+        ("w" | "week" | "weeks", Operation::Add) => date.checked_add_days(Days::new(amount as u64 * 7)),
+        ("w" | "week" | "weeks", Operation::Subtract) => {
+            date.checked_sub_days(Days::new(amount as u64 * 7))
+        }
+        ("m" | "month" | "months", Operation::Add) => date.checked_add_months(Months::new(amount)),
+        ("m" | "month" | "months", Operation::Subtract) => {
+            date.checked_sub_months(Months::new(amount))
+        }
+        ("y" | "year" | "years", Operation::Add) => {
+            date.checked_add_months(Months::new(amount.checked_mul(12)?))
+        }
+        ("y" | "year" | "years", Operation::Subtract) => {
+            date.checked_sub_months(Months::new(amount.checked_mul(12)?))
+        }
+        _ => None,
+    }
+}
+
+/// Formats a day count like "45 days (6 weeks and 3 days)"
+pub fn describe_days(days: i64) -> String {
+    if days.abs() < 7 {
+        return plural(days, "day");
+    }
+
+    format!("{} ({})", plural(days, "day"), describe_weeks(days.abs()))
+}
+
+/// "6 weeks and 2 days", or "6 weeks" for whole weeks
+pub fn describe_weeks(days: i64) -> String {
+    match days % 7 {
+        0 => plural(days / 7, "week"),
+        rest => format!("{} and {}", plural(days / 7, "week"), plural(rest, "day")),
+    }
+}
+
+fn plural(n: i64, unit: &str) -> String {
+    format!("{} {}{}", n, unit, if n.abs() == 1 { "" } else { "s" })
+}
+
+/// The formats a date can be copied as, with a name for each one
+pub fn date_formats(date: &NaiveDate) -> Vec<(&'static str, String)> {
+    vec![
+        ("ISO 8601", date.format("%Y-%m-%d").to_string()),
+        ("Long", date.format("%A, %B %-d, %Y").to_string()),
+        ("Short", date.format("%a, %d %b %Y").to_string()),
+        ("Day/month/year", date.format("%d/%m/%Y").to_string()),
+        ("Month/day/year", date.format("%m/%d/%Y").to_string()),
+    ]
+}
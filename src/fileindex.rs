@@ -0,0 +1,410 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use ignore::{Match, WalkBuilder, gitignore::Gitignore};
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask, Watches};
+
+use crate::{config, fsutil};
+
+const INDEX_FILE_NAME: &str = "file-index";
+const MAX_RESULTS: usize = 10;
+const INOTIFY_BUFFER_SIZE: usize = 64 * 1024;
+// changes reported by inotify are written to disk at most this often
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+// ignore files the crawl honors, the first one has precedence in the same folder
+const IGNORE_FILE_NAMES: [&str; 2] = [".ignore", ".gitignore"];
+
+// (command, database) pairs, the database only has to exist for the command to be used
+const LOCATE_DATABASES: [(&str, &str); 2] = [
+    ("plocate", "/var/lib/plocate/plocate.db"),
+    ("locate", "/var/lib/mlocate/mlocate.db"),
+];
+
+// path -> lowercase path, queries are matched against it and its file name
+type Files = BTreeMap<String, String>;
+
+#[derive(Debug, Default)]
+struct LocateState {
+    // the terms of the last finished query and the paths it found
+    results: Option<(Vec<String>, Vec<PathBuf>)>,
+    running: bool,
+}
+
+/// Index of the files under the configured roots, used by "f <query>".
+///
+/// The last index is read from the cache dir at startup, then the roots are crawled
+/// again in a background thread (honoring `.gitignore`, `.ignore` and the configured
+/// exclusions) and kept up to date with inotify. Until there is an index to search,
+/// queries go to plocate/mlocate in a background thread if one of their databases exists.
+#[derive(Debug)]
+pub struct FileIndex {
+    files: Arc<RwLock<Files>>,
+    ready: Arc<AtomicBool>,
+    locate_state: Arc<Mutex<LocateState>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl FileIndex {
+    pub fn new(roots: &[String], excludes: &[String], updated: Arc<AtomicBool>) -> Self {
+        let files = Arc::new(RwLock::new(BTreeMap::new()));
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let roots: Vec<PathBuf> = if roots.is_empty() {
+            vec![PathBuf::from(fsutil::expand_home("~"))]
+        } else {
+            roots.iter().map(|it| PathBuf::from(fsutil::expand_home(it))).collect()
+        };
+        let excludes = excludes.to_vec();
+
+        let files_clone = files.clone();
+        let ready_clone = ready.clone();
+        let updated_clone = updated.clone();
+        thread::spawn(move || {
+            let updated = updated_clone;
+            let index_path = config::cache_dir().join(INDEX_FILE_NAME);
+            if let Ok(saved) = load(&index_path) {
+                *files_clone.write().expect("FileIndex poisoned") = saved;
+                ready_clone.store(true, Ordering::Relaxed);
+                updated.store(true, Ordering::Relaxed);
+            }
+
+            let mut crawled = BTreeMap::new();
+            let mut dirs = Vec::new();
+            for root in &roots {
+                crawl(root, &excludes, &mut crawled, &mut dirs);
+            }
+            if let Err(e) = save(&index_path, &crawled) {
+                println!("unable to save file index to {}: {}", index_path.to_string_lossy(), e);
+            }
+            *files_clone.write().expect("FileIndex poisoned") = crawled;
+            ready_clone.store(true, Ordering::Relaxed);
+            updated.store(true, Ordering::Relaxed);
+
+            watch(&index_path, &excludes, dirs, &files_clone, &updated);
+        });
+
+        Self {
+            files,
+            ready,
+            locate_state: Arc::new(Mutex::new(LocateState::default())),
+            updated,
+        }
+    }
+
+    /// Files with every word of the query in their name (or in their path, for
+    /// words with a `/`), exact names first, then prefixes, then shallower paths
+    pub fn search(&self, query: &str) -> Vec<PathBuf> {
+        let terms: Vec<String> = query.split_whitespace().map(|it| it.to_lowercase()).collect();
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        if !self.ready.load(Ordering::Relaxed) {
+            return self.locate_in_background(&terms);
+        }
+
+        let files = self.files.read().expect("FileIndex poisoned");
+        let query = terms.join(" ");
+        let mut matches: Vec<((u8, usize, usize), &String)> = files
+            .iter()
+            .map(|(path, lowercase_path)| (path, lowercase_path, lowercase_name(lowercase_path)))
+            .filter(|(_, lowercase_path, name)| {
+                terms.iter().all(|term| {
+                    if term.contains('/') {
+                        lowercase_path.contains(term)
+                    } else {
+                        name.contains(term)
+                    }
+                })
+            })
+            .map(|(path, _, name)| {
+                let kind = if name == query {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else {
+                    2
+                };
+                ((kind, path.matches('/').count(), path.len()), path)
+            })
+            .collect();
+
+        matches.sort();
+        matches
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, path)| PathBuf::from(path))
+            .collect()
+    }
+
+    // locate can take a while on big databases, its results are picked up
+    // by the search that runs once `updated` is set
+    fn locate_in_background(&self, terms: &[String]) -> Vec<PathBuf> {
+        let mut state = self.locate_state.lock().expect("FileIndex poisoned");
+        if let Some((located_terms, paths)) = &state.results
+            && located_terms == terms
+        {
+            return paths.clone();
+        }
+
+        // queries typed meanwhile run once this one is done
+        if !state.running {
+            state.running = true;
+            let terms = terms.to_vec();
+            let locate_state = self.locate_state.clone();
+            let updated = self.updated.clone();
+            thread::spawn(move || {
+                let paths = locate(&terms);
+                let mut state = locate_state.lock().expect("FileIndex poisoned");
+                state.results = Some((terms, paths));
+                state.running = false;
+                updated.store(true, Ordering::Relaxed);
+            });
+        }
+
+        vec![]
+    }
+}
+
+// what's stored for each path, so searching never lowercases them again
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+fn lowercase_name(lowercase_path: &str) -> &str {
+    lowercase_path.rsplit('/').next().unwrap_or_default()
+}
+
+fn is_excluded(path: &Path, excludes: &[String]) -> bool {
+    excludes.iter().any(|exclude| {
+        if exclude.contains('/') {
+            path.starts_with(fsutil::expand_home(exclude))
+        } else {
+            path.file_name().is_some_and(|name| name.to_string_lossy() == *exclude)
+        }
+    })
+}
+
+// the crawl never walks into hidden or ignored entries, but paths reported by inotify
+// are checked one by one, against the ignore files of every folder above them
+fn is_ignored(path: &Path, is_dir: bool) -> bool {
+    if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+        return true;
+    }
+
+    for dir in path.ancestors().skip(1) {
+        for name in IGNORE_FILE_NAMES {
+            let ignore_file = dir.join(name);
+            if !ignore_file.is_file() {
+                continue;
+            }
+
+            let (gitignore, _) = Gitignore::new(&ignore_file);
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => (),
+            }
+        }
+    }
+
+    false
+}
+
+fn crawl(root: &Path, excludes: &[String], files: &mut Files, dirs: &mut Vec<PathBuf>) {
+    let excludes_clone = excludes.to_vec();
+    let walker = WalkBuilder::new(root)
+        // honor .gitignore even in directories that aren't inside a repository
+        .require_git(false)
+        .filter_entry(move |entry| !is_excluded(entry.path(), &excludes_clone))
+        .build();
+
+    for entry in walker.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_some_and(|it| it.is_dir()) {
+            dirs.push(path.to_path_buf());
+        }
+        // paths with new lines can't be stored in the index file
+        let path_str = path.to_string_lossy();
+        if !path_str.contains('\n') {
+            files.insert(path_str.to_string(), path_key(path));
+        }
+    }
+}
+
+fn watch(
+    index_path: &Path,
+    excludes: &[String],
+    dirs: Vec<PathBuf>,
+    files: &RwLock<Files>,
+    updated: &AtomicBool,
+) {
+    let mut inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            println!("unable to watch indexed files for changes: {}", e);
+            return;
+        }
+    };
+    let mut watches = inotify.watches();
+    let mut watched: HashMap<WatchDescriptor, PathBuf> = HashMap::new();
+    add_watches(&mut watches, &mut watched, dirs);
+
+    let mut last_save = Instant::now();
+    let mut buffer = vec![0; INOTIFY_BUFFER_SIZE];
+    loop {
+        let events: Vec<(WatchDescriptor, EventMask, Option<PathBuf>)> =
+            match inotify.read_events_blocking(&mut buffer) {
+                Ok(events) => events
+                    .map(|e| (e.wd, e.mask, e.name.map(PathBuf::from)))
+                    .collect(),
+                Err(e) => {
+                    println!("stopped watching indexed files: {}", e);
+                    return;
+                }
+            };
+
+        let mut files = files.write().expect("FileIndex poisoned");
+        for (wd, mask, name) in events {
+            if mask.contains(EventMask::IGNORED) {
+                watched.remove(&wd);
+                continue;
+            }
+
+            let path = match (watched.get(&wd), name) {
+                (Some(dir), Some(name)) => dir.join(name),
+                _ => continue,
+            };
+            if is_excluded(&path, excludes) {
+                continue;
+            }
+
+            if mask.intersects(EventMask::CREATE | EventMask::MOVED_TO) {
+                let is_dir = mask.contains(EventMask::ISDIR);
+                if is_ignored(&path, is_dir) {
+                    continue;
+                }
+
+                if is_dir {
+                    let mut new_dirs = Vec::new();
+                    crawl(&path, excludes, &mut files, &mut new_dirs);
+                    add_watches(&mut watches, &mut watched, new_dirs);
+                } else {
+                    files.insert(path.to_string_lossy().to_string(), path_key(&path));
+                }
+            } else if mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+                let path_str = path.to_string_lossy().to_string();
+                files.remove(&path_str);
+
+                // children sort right after their folder, so only those are visited
+                let children_prefix = format!("{}/", path_str);
+                let children: Vec<String> = files
+                    .range(children_prefix.clone()..)
+                    .map(|(it, _)| it)
+                    .take_while(|it| it.starts_with(&children_prefix))
+                    .cloned()
+                    .collect();
+                for child in children {
+                    files.remove(&child);
+                }
+            }
+        }
+
+        updated.store(true, Ordering::Relaxed);
+        if last_save.elapsed() >= SAVE_INTERVAL {
+            last_save = Instant::now();
+            if let Err(e) = save(index_path, &files) {
+                println!("unable to save file index to {}: {}", index_path.to_string_lossy(), e);
+            }
+        }
+    }
+}
+
+fn add_watches(watches: &mut Watches, watched: &mut HashMap<WatchDescriptor, PathBuf>, dirs: Vec<PathBuf>) {
+    let mask = WatchMask::CREATE
+        | WatchMask::DELETE
+        | WatchMask::MOVED_FROM
+        | WatchMask::MOVED_TO
+        | WatchMask::ONLYDIR;
+    for dir in dirs {
+        match watches.add(&dir, mask) {
+            Ok(wd) => {
+                watched.insert(wd, dir);
+            }
+            Err(e) => {
+                // most likely fs.inotify.max_user_watches was reached,
+                // there's no point in trying the other ones
+                println!("unable to watch {}: {}", dir.to_string_lossy(), e);
+                return;
+            }
+        }
+    }
+}
+
+fn load(index_path: &Path) -> io::Result<Files> {
+    let content = fs::read_to_string(index_path)?;
+    Ok(content
+        .lines()
+        .map(|path| (path.to_string(), path.to_lowercase()))
+        .collect())
+}
+
+// written to a temporary file first, so a crash never leaves a truncated index
+fn save(index_path: &Path, files: &Files) -> io::Result<()> {
+    if let Some(dir) = index_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let tmp_path = index_path.with_extension("tmp");
+    let mut writer = io::BufWriter::new(fs::File::create(&tmp_path)?);
+    for path in files.keys() {
+        writeln!(writer, "{}", path)?;
+    }
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(tmp_path, index_path)
+}
+
+fn locate(terms: &[String]) -> Vec<PathBuf> {
+    let command = LOCATE_DATABASES
+        .iter()
+        .find(|(command, database)| {
+            Path::new(database).exists() && fsutil::find_in_path(command).is_some()
+        })
+        .map(|(command, _)| command);
+    let command = match command {
+        Some(command) => command,
+        None => return vec![],
+    };
+
+    // -A makes every term required instead of any of them
+    let limit = MAX_RESULTS.to_string();
+    let output = Command::new(command)
+        .args(["-i", "-A", "-l", &limit, "--"])
+        .args(terms)
+        .output();
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect(),
+        Err(e) => {
+            println!("unable to run {}: {}", command, e);
+            vec![]
+        }
+    }
+}
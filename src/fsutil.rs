@@ -1,6 +1,32 @@
-use std::path::Path;
+use std::{
+    env,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
 
 pub fn is_dir_path(path_str: &str) -> bool {
     let p = Path::new(path_str);
     p.is_dir()
 }
+
+pub fn expand_home(path_str: &str) -> String {
+    let home_path = env::var("HOME").expect("expected $HOME to always be defined");
+    if path_str.starts_with('~') {
+        path_str.replacen("~", &home_path, 1)
+    } else {
+        path_str.to_string()
+    }
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// Finds an executable by name in the directories of `$PATH`
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = env::var("PATH").ok()?;
+    env::split_paths(&path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
@@ -0,0 +1,269 @@
+use rand::{Rng, seq::IndexedRandom};
+use regex::Regex;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>/?~";
+
+const DEFAULT_PASSWORD_LENGTH: usize = 20;
+const MAX_PASSWORD_LENGTH: usize = 1024;
+const MAX_DICE: u32 = 1000;
+const MAX_LOREM_COUNT: usize = 100;
+
+const LOREM_WORDS: [&str; 64] = [
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do",
+    "eiusmod", "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim",
+    "ad", "minim", "veniam", "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi",
+    "aliquip", "ex", "ea", "commodo", "consequat", "duis", "aute", "irure", "in", "reprehenderit",
+    "voluptate", "velit", "esse", "cillum", "fugiat", "nulla", "pariatur", "excepteur", "sint",
+    "occaecat", "cupidatat", "non", "proident", "sunt", "culpa", "qui", "officia", "deserunt",
+    "mollit", "anim", "id", "est", "laborum", "perspiciatis", "unde",
+];
+
+#[derive(Debug, Clone)]
+pub struct PasswordOptions {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiceRoll {
+    pub rolls: Vec<u32>,
+    pub modifier: i64,
+    pub total: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoremUnit {
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+impl Default for PasswordOptions {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_PASSWORD_LENGTH,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+}
+
+impl PasswordOptions {
+    /// Parses the arguments of "password 24 nosymbols", where every argument is optional
+    pub fn parse(args: &str) -> Option<Self> {
+        let mut opts = Self::default();
+        for arg in args.split_whitespace() {
+            match arg.to_lowercase().as_str() {
+                "nosymbols" | "alnum" => opts.symbols = false,
+                "nodigits" => opts.digits = false,
+                "noupper" => opts.uppercase = false,
+                "nolower" => opts.lowercase = false,
+                "pin" | "digits" => {
+                    opts.lowercase = false;
+                    opts.uppercase = false;
+                    opts.symbols = false;
+                    opts.digits = true;
+                }
+                n => opts.length = n.parse().ok().filter(|n| *n > 0 && *n <= MAX_PASSWORD_LENGTH)?,
+            }
+        }
+
+        if opts.pool().is_empty() {
+            return None;
+        }
+
+        Some(opts)
+    }
+
+    fn classes(&self) -> Vec<&'static str> {
+        let mut classes = Vec::new();
+        if self.lowercase {
+            classes.push(LOWERCASE);
+        }
+        if self.uppercase {
+            classes.push(UPPERCASE);
+        }
+        if self.digits {
+            classes.push(DIGITS);
+        }
+        if self.symbols {
+            classes.push(SYMBOLS);
+        }
+
+        classes
+    }
+
+    fn pool(&self) -> Vec<char> {
+        self.classes().iter().flat_map(|c| c.chars()).collect()
+    }
+
+    pub fn entropy_bits(&self) -> f64 {
+        self.length as f64 * (self.pool().len() as f64).log2()
+    }
+
+    pub fn describe(&self) -> String {
+        let names: Vec<&str> = [
+            (self.lowercase, "lowercase"),
+            (self.uppercase, "uppercase"),
+            (self.digits, "digits"),
+            (self.symbols, "symbols"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, name)| *name)
+        .collect();
+
+        format!(
+            "{} characters ({}), ~{:.0} bits of entropy",
+            self.length,
+            names.join(", "),
+            self.entropy_bits()
+        )
+    }
+}
+
+pub fn uuid_v4() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+pub fn uuid_v7() -> String {
+    uuid::Uuid::now_v7().to_string()
+}
+
+pub fn ulid() -> String {
+    ulid::Ulid::new().to_string()
+}
+
+pub fn password(opts: &PasswordOptions) -> String {
+    let mut rng = rand::rng();
+    let pool = opts.pool();
+    let classes = opts.classes();
+
+    // guarantee one character of every class, as long as the length allows it
+    let mut chars: Vec<char> = classes
+        .iter()
+        .take(opts.length)
+        .map(|class| {
+            let class_chars: Vec<char> = class.chars().collect();
+            *class_chars.choose(&mut rng).unwrap()
+        })
+        .collect();
+    while chars.len() < opts.length {
+        chars.push(*pool.choose(&mut rng).unwrap());
+    }
+
+    // the guaranteed characters would otherwise always be at the start
+    for i in (1..chars.len()).rev() {
+        let j = rng.random_range(0..=i);
+        chars.swap(i, j);
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Parses ranges like "1-100", "1..100" or "-5 to 5"
+pub fn parse_range(args: &str) -> Option<(i64, i64)> {
+    let range_regex = Regex::new(r"^(-?\d+)\s*(?:-|\.\.|to)\s*(-?\d+)$").unwrap();
+    let captures = range_regex.captures(args.trim())?;
+    let a: i64 = captures[1].parse().ok()?;
+    let b: i64 = captures[2].parse().ok()?;
+
+    Some((a.min(b), a.max(b)))
+}
+
+pub fn random_in_range(min: i64, max: i64) -> i64 {
+    rand::rng().random_range(min..=max)
+}
+
+/// Rolls dice written in the usual notation, like "3d6", "d20" or "2d8+3"
+pub fn roll_dice(notation: &str) -> Option<DiceRoll> {
+    let dice_regex = Regex::new(r"^(\d*)d(\d+)\s*([+-]\s*\d+)?$").unwrap();
+    let notation = notation.trim().to_lowercase();
+    let captures = dice_regex.captures(&notation)?;
+
+    let count: u32 = match &captures[1] {
+        "" => 1,
+        n => n.parse().ok()?,
+    };
+    let sides: u32 = captures[2].parse().ok()?;
+    let modifier: i64 = match captures.get(3) {
+        Some(m) => m.as_str().replace(' ', "").parse().ok()?,
+        None => 0,
+    };
+
+    if count == 0 || count > MAX_DICE || sides == 0 {
+        return None;
+    }
+
+    let mut rng = rand::rng();
+    let rolls: Vec<u32> = (0..count).map(|_| rng.random_range(1..=sides)).collect();
+    let total = rolls.iter().map(|r| *r as i64).sum::<i64>() + modifier;
+
+    Some(DiceRoll {
+        rolls,
+        modifier,
+        total,
+    })
+}
+
+/// Parses "3 paragraphs", "20 words", "paragraph" and similar
+pub fn parse_lorem_args(args: &str) -> Option<(usize, LoremUnit)> {
+    let mut count = 1;
+    let mut unit = LoremUnit::Paragraphs;
+    for arg in args.split_whitespace() {
+        match arg.to_lowercase().as_str() {
+            "word" | "words" => unit = LoremUnit::Words,
+            "sentence" | "sentences" => unit = LoremUnit::Sentences,
+            "paragraph" | "paragraphs" => unit = LoremUnit::Paragraphs,
+            n => count = n.parse().ok().filter(|n| *n > 0 && *n <= MAX_LOREM_COUNT)?,
+        }
+    }
+
+    Some((count, unit))
+}
+
+pub fn lorem(count: usize, unit: LoremUnit) -> String {
+    let mut rng = rand::rng();
+    match unit {
+        LoremUnit::Words => lorem_words(&mut rng, count),
+        LoremUnit::Sentences => (0..count)
+            .map(|_| lorem_sentence(&mut rng))
+            .collect::<Vec<String>>()
+            .join(" "),
+        LoremUnit::Paragraphs => (0..count)
+            .map(|_| {
+                let sentences = rng.random_range(4..=7);
+                (0..sentences)
+                    .map(|_| lorem_sentence(&mut rng))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n"),
+    }
+}
+
+fn lorem_words(rng: &mut impl Rng, count: usize) -> String {
+    (0..count)
+        .map(|_| *LOREM_WORDS.choose(rng).unwrap())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn lorem_sentence(rng: &mut impl Rng) -> String {
+    let len = rng.random_range(6..=14);
+    let words = lorem_words(rng, len);
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
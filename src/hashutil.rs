@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Instant, SystemTime},
+};
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+// results kept for files hashed earlier, the least recently asked for go first
+const MAX_CACHED_HASHES: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    Blake3,
+    Crc32,
+}
+
+#[derive(Debug, Clone)]
+pub struct HashDigest {
+    pub algorithm: Algorithm,
+    pub hex: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum FileHashState {
+    InProgress { hashed: u64, total: u64 },
+    Done(Vec<HashDigest>),
+    Failed(String),
+}
+
+impl Algorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Sha1 => "SHA-1",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Blake3 => "BLAKE3",
+            Algorithm::Crc32 => "CRC32",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "md5",
+            Algorithm::Sha1 => "sha1",
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha512 => "sha512",
+            Algorithm::Blake3 => "blake3",
+            Algorithm::Crc32 => "crc32",
+        }
+    }
+
+    pub fn from_prefix(prefix: &str) -> Option<Algorithm> {
+        match prefix.to_lowercase().as_str() {
+            "md5" => Some(Algorithm::Md5),
+            "sha1" | "sha-1" => Some(Algorithm::Sha1),
+            "sha256" | "sha-256" => Some(Algorithm::Sha256),
+            "sha512" | "sha-512" => Some(Algorithm::Sha512),
+            "blake3" | "b3" => Some(Algorithm::Blake3),
+            "crc32" | "crc" => Some(Algorithm::Crc32),
+            _ => None,
+        }
+    }
+
+    /// Algorithms whose hex digest has the same length as `hex`,
+    /// used to figure out what a pasted checksum is
+    pub fn candidates_for_digest(hex: &str) -> Vec<Algorithm> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return vec![];
+        }
+
+        match hex.len() {
+            8 => vec![Algorithm::Crc32],
+            32 => vec![Algorithm::Md5],
+            40 => vec![Algorithm::Sha1],
+            64 => vec![Algorithm::Sha256, Algorithm::Blake3],
+            128 => vec![Algorithm::Sha512],
+            _ => vec![],
+        }
+    }
+}
+
+/// Computes every supported digest in a single pass over the data
+pub struct MultiHasher {
+    md5: Md5,
+    sha1: Sha1,
+    sha256: Sha256,
+    sha512: Sha512,
+    blake3: blake3::Hasher,
+    crc32: crc32fast::Hasher,
+}
+
+impl MultiHasher {
+    pub fn new() -> Self {
+        Self {
+            md5: Md5::new(),
+            sha1: Sha1::new(),
+            sha256: Sha256::new(),
+            sha512: Sha512::new(),
+            blake3: blake3::Hasher::new(),
+            crc32: crc32fast::Hasher::new(),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.md5.update(data);
+        self.sha1.update(data);
+        self.sha256.update(data);
+        self.sha512.update(data);
+        self.blake3.update(data);
+        self.crc32.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<HashDigest> {
+        let digest = |algorithm, hex| HashDigest { algorithm, hex };
+        vec![
+            digest(Algorithm::Md5, hex::encode(self.md5.finalize())),
+            digest(Algorithm::Sha1, hex::encode(self.sha1.finalize())),
+            digest(Algorithm::Sha256, hex::encode(self.sha256.finalize())),
+            digest(Algorithm::Sha512, hex::encode(self.sha512.finalize())),
+            digest(Algorithm::Blake3, self.blake3.finalize().to_hex().to_string()),
+            digest(Algorithm::Crc32, format!("{:08x}", self.crc32.finalize())),
+        ]
+    }
+}
+
+pub fn hash_bytes(data: &[u8]) -> Vec<HashDigest> {
+    let mut hasher = MultiHasher::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn hash_file(path: &Path, mut on_progress: impl FnMut(u64)) -> io::Result<Vec<HashDigest>> {
+    let mut file = File::open(path)?;
+    let mut hasher = MultiHasher::new();
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+    let mut hashed: u64 = 0;
+
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..n]);
+        hashed += n as u64;
+        on_progress(hashed);
+    }
+
+    Ok(hasher.finalize())
+}
+
+#[derive(Debug)]
+struct HashJob {
+    // modification time of the file when it was hashed
+    modified: Option<SystemTime>,
+    state: FileHashState,
+    last_used: Instant,
+}
+
+type HashJobs = HashMap<PathBuf, HashJob>;
+
+/// Keeps track of file hashes computed in background threads.
+///
+/// Results are kept per path and invalidated when the file modification time
+/// changes, only the last `MAX_CACHED_HASHES` files are remembered. Every progress
+/// update sets the shared `updated` flag so the UI knows it has to refresh the suggestions.
+#[derive(Debug)]
+pub struct FileHashCache {
+    jobs: Arc<Mutex<HashJobs>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl FileHashCache {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            updated,
+        }
+    }
+
+    pub fn get_or_start(&self, path: &Path) -> FileHashState {
+        let metadata = match path.metadata() {
+            Ok(m) => m,
+            Err(e) => return FileHashState::Failed(e.to_string()),
+        };
+        let modified = metadata.modified().ok();
+        let total = metadata.len();
+
+        let mut jobs = self.jobs.lock().expect("FileHashCache poisoned");
+        if let Some(job) = jobs.get_mut(path)
+            && job.modified == modified
+        {
+            job.last_used = Instant::now();
+            return job.state.clone();
+        }
+
+        let state = FileHashState::InProgress { hashed: 0, total };
+        jobs.insert(
+            path.to_path_buf(),
+            HashJob {
+                modified,
+                state: state.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        evict_least_recently_used(&mut jobs);
+
+        let jobs_clone = self.jobs.clone();
+        let updated = self.updated.clone();
+        let path = path.to_path_buf();
+        thread::spawn(move || {
+            // evicted or restarted jobs don't get their state back
+            let set_state = |state: FileHashState| {
+                let mut jobs = jobs_clone.lock().expect("FileHashCache poisoned");
+                if let Some(job) = jobs.get_mut(&path)
+                    && job.modified == modified
+                {
+                    job.state = state;
+                    updated.store(true, Ordering::Relaxed);
+                }
+            };
+
+            let mut last_percent = 0;
+            let result = hash_file(&path, |hashed| {
+                let percent = (hashed * 100).checked_div(total).unwrap_or(100);
+                if percent != last_percent {
+                    last_percent = percent;
+                    set_state(FileHashState::InProgress { hashed, total });
+                }
+            });
+
+            match result {
+                Ok(digests) => set_state(FileHashState::Done(digests)),
+                Err(e) => set_state(FileHashState::Failed(e.to_string())),
+            }
+        });
+
+        state
+    }
+}
+
+fn evict_least_recently_used(jobs: &mut HashJobs) {
+    while jobs.len() > MAX_CACHED_HASHES {
+        let oldest = jobs
+            .iter()
+            .min_by_key(|(_, job)| job.last_used)
+            .map(|(path, _)| path.clone());
+        match oldest {
+            Some(path) => jobs.remove(&path),
+            None => return,
+        };
+    }
+}
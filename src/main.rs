@@ -1,24 +1,60 @@
+mod charmap;
+mod clipboard;
+mod clipboardhistory;
+mod codecutil;
 mod component;
+mod config;
+mod contentsearch;
+mod dateutil;
+mod fileindex;
 mod fsutil;
+mod genutil;
+mod hashutil;
 mod conversionutil;
 mod mathutils;
+mod pathindex;
+mod processmgr;
+mod quicklink;
+mod recentfiles;
+mod searchengine;
+mod servicemgr;
 mod sessionmgr;
+mod shellhistory;
+mod shellutil;
+mod snippet;
 mod suggestions;
 mod sysaction;
 mod sysinfo;
+mod timeutil;
+mod trashutil;
+mod urlutil;
+mod windowmgr;
 
-use std::sync::{Arc, Mutex};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use component::suggestion_row::{SuggestionRow, SuggestionRowData};
+use component::{
+    action_panel::ActionPanel,
+    output_panel::OutputPanel,
+    suggestion_row::{SuggestionRow, SuggestionRowData},
+};
 use gtk4::gio::{self};
 use mathutils::*;
 use suggestions::{PostRunAction, SuggestionMgr};
 
 use gtk::prelude::*;
 use gtk::{Application, ApplicationWindow, glib};
-use gtk4::gdk::Key;
+use gtk4::gdk::{Key, ModifierType};
 use gtk4::{self as gtk, EventControllerKey, ScrolledWindow, gdk};
-use sysaction::find;
+
+pub const APPLICATION_ID: &str = "com.github.luizgfc.automata";
+const BACKGROUND_REFRESH_INTERVAL_MS: u64 = 100;
+// records the clipboard history instead of showing the launcher, meant to be
+// started with the session
+const CLIPBOARD_DAEMON_FLAG: &str = "--clipboard-daemon";
 
 fn load_css() {
     let display = gdk::Display::default().expect("unable to load default display");
@@ -38,11 +74,89 @@ fn load_css() {
     );
 }
 
+// has to be called without holding the SuggestionMgr lock, changing the input
+// updates the suggestions, which would deadlock
+fn handle_post_run_action(
+    post_run_action: PostRunAction,
+    window: &ApplicationWindow,
+    main_input: &gtk::Entry,
+    output_panel: &OutputPanel,
+) {
+    match post_run_action {
+        PostRunAction::Nothing => (),
+        PostRunAction::Close => window.close(),
+        PostRunAction::ShowOutput(process) => output_panel.show_process(process),
+        PostRunAction::SetInput(input) => {
+            main_input.set_text(&input);
+            main_input.set_position(-1);
+        }
+    }
+}
+
+// the selection is kept at the same position when given, for redraws the user didn't cause
+fn rebuild_list(
+    list_store: &gio::ListStore,
+    mgr: &SuggestionMgr,
+    keep_selection: Option<&gtk::SingleSelection>,
+) {
+    let selected = keep_selection.map(|it| (it, it.selected()));
+    list_store.remove_all();
+
+    for it in mgr.get_suggestions() {
+        list_store.append(&SuggestionRowData::new(
+            &it.id,
+            &it.title,
+            &it.description,
+            it.icon_path.clone(),
+        ));
+    }
+
+    if let Some((selection_model, selected)) = selected
+        && selected < selection_model.n_items()
+    {
+        selection_model.set_selected(selected);
+    }
+}
+
+// Shift, Ctrl and Alt + Enter run the second, third and fourth actions of a suggestion
+fn action_index_for_modifiers(modifiers: ModifierType) -> usize {
+    if modifiers.contains(ModifierType::SHIFT_MASK) {
+        1
+    } else if modifiers.contains(ModifierType::CONTROL_MASK) {
+        2
+    } else if modifiers.contains(ModifierType::ALT_MASK) {
+        3
+    } else {
+        0
+    }
+}
+
 fn main() -> glib::ExitCode {
+    if env::args().any(|arg| arg == clipboard::X11_SERVE_FLAG) {
+        return match clipboard::serve_x11_from_stdin() {
+            Ok(()) => glib::ExitCode::SUCCESS,
+            Err(e) => {
+                println!("unable to serve the X11 clipboard: {}", e);
+                glib::ExitCode::FAILURE
+            }
+        };
+    }
+
+    if env::args().any(|arg| arg == CLIPBOARD_DAEMON_FLAG) {
+        let config = config::Config::load();
+        return match clipboardhistory::run_daemon(config.clipboard_max_entries, config.clipboard_primary) {
+            Ok(()) => glib::ExitCode::SUCCESS,
+            Err(e) => {
+                println!("unable to watch the clipboard: {}", e);
+                glib::ExitCode::FAILURE
+            }
+        };
+    }
+
     let suggestion_mgr = Arc::new(Mutex::new(SuggestionMgr::new()));
 
     let app = Application::builder()
-        .application_id("com.github.luizgfc.automata")
+        .application_id(APPLICATION_ID)
         .build();
 
     app.connect_activate(move |app| {
@@ -65,6 +179,14 @@ fn main() -> glib::ExitCode {
         let main_input = gtk::Entry::new();
         main_input.add_css_class("main-input");
 
+        // output of commands run with "Run and show output", hidden until there is one
+        let output_panel = OutputPanel::default();
+        output_panel.set_visible(false);
+
+        // every action of the selected suggestion, opened with Ctrl+K or Right
+        let action_panel = ActionPanel::default();
+        action_panel.set_visible(false);
+
         let list_store = gio::ListStore::new::<SuggestionRowData>();
         let factory = gtk::SignalListItemFactory::new();
         factory.connect_setup(move |_factory, item| {
@@ -97,28 +219,44 @@ fn main() -> glib::ExitCode {
 
         let suggestion_mgr_clone = suggestion_mgr.clone();
         let list_store_clone = list_store.clone();
+        let action_panel_clone = action_panel.clone();
         main_input.connect_changed(move |input| {
             dbg!("main_input.connect_changed");
+            action_panel_clone.hide();
             let input_str: String = input.text().into();
             let mut mgr = suggestion_mgr_clone
                 .lock()
                 .expect("unable to lock suggestion list");
 
             mgr.update(&input_str);
-            list_store_clone.remove_all();
+            rebuild_list(&list_store_clone, &mgr, None);
+        });
 
-            for it in mgr.get_suggestions() {
-                list_store_clone.append(&SuggestionRowData::new(
-                    &it.id,
-                    &it.title,
-                    &it.description,
-                    it.icon_path.clone(),
-                ));
+        // background jobs (like file hashing) report results after the input changed,
+        // so poll for them and redraw the list keeping the current selection
+        let suggestion_mgr_clone = suggestion_mgr.clone();
+        let list_store_clone = list_store.clone();
+        let selection_model = gtk::SingleSelection::new(Some(list_store));
+        let selection_model_clone = selection_model.clone();
+        let action_panel_clone = action_panel.clone();
+        glib::timeout_add_local(Duration::from_millis(BACKGROUND_REFRESH_INTERVAL_MS), move || {
+            let mut mgr = suggestion_mgr_clone
+                .lock()
+                .expect("unable to lock suggestion list");
+
+            if mgr.refresh() {
+                // the actions it lists may be gone or in a different order now
+                action_panel_clone.hide();
+                rebuild_list(&list_store_clone, &mgr, Some(&selection_model_clone));
             }
+
+            glib::ControlFlow::Continue
         });
 
-        let selection_model = gtk::SingleSelection::new(Some(list_store));
         let list_view = gtk::ListView::new(Some(selection_model.clone()), Some(factory));
+        let window_clone = window.clone();
+        let main_input_clone = main_input.clone();
+        let output_panel_clone = output_panel.clone();
         let suggestion_mgr_clone = suggestion_mgr.clone();
         list_view.connect_activate(move |list_view, position| {
             let model = list_view.model().unwrap();
@@ -126,10 +264,16 @@ fn main() -> glib::ExitCode {
                 .item(position)
                 .and_downcast::<SuggestionRowData>()
                 .expect("selected item should always be able to downcast to the type defined for its row");
-            {
+            let post_run_action = {
                 let mgr = suggestion_mgr_clone.lock().expect("SuggestionMgr poisoned");
-                mgr.run_by_id(&row_data.id());
-            }
+                mgr.run_by_id(&row_data.id(), 0)
+            };
+            handle_post_run_action(
+                post_run_action,
+                &window_clone,
+                &main_input_clone,
+                &output_panel_clone,
+            );
         });
 
         let suggestion_list_scrollable = ScrolledWindow::builder()
@@ -139,38 +283,117 @@ fn main() -> glib::ExitCode {
             .build();
 
         let window_clone = window.clone();
+        let output_panel_clone = output_panel.clone();
+        let action_panel_clone = action_panel.clone();
         let suggestion_mgr_clone = suggestion_mgr.clone();
         let selection_model_clone = selection_model.clone();
-        main_input.connect_activate(move |_| {
+        main_input.connect_activate(move |input| {
             dbg!("main_input.connect_activate");
-            let selected = selection_model_clone.selected_item(); 
-            if let None = selected {
-                return;
-            }
+            // with the actions panel open Enter runs the action selected there
+            let (id, action_index) = match action_panel_clone.selected_action() {
+                Some(selected_action) => selected_action,
+                None => match selection_model_clone.selected_item() {
+                    Some(selected) => {
+                        let row_data = selected.downcast::<SuggestionRowData>()
+                            .expect("selected item should always be able to downcast to the type defined for its row");
+                        (row_data.id(), 0)
+                    }
+                    None => return,
+                },
+            };
+            action_panel_clone.hide();
 
-            let row_data = selected.and_downcast::<SuggestionRowData>()
-                .expect("selected item should always be able to downcast to the type defined for its row");
-            {
+            let post_run_action = {
                 let mgr = suggestion_mgr_clone.lock().expect("SuggestionMgr poisoned");
-                let post_run_action = mgr.run_by_id(&row_data.id());
-
-                if let PostRunAction::Close = post_run_action {
-                    window_clone.close();
-                }
-            }
+                mgr.run_by_id(&id, action_index)
+            };
+            handle_post_run_action(post_run_action, &window_clone, input, &output_panel_clone);
         });
 
+        // handled before the input gets them, as it would take Enter and the arrows for itself
         let key_controller = EventControllerKey::new();
+        key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
         let window_clone = window.clone();
         let suggestion_mgr_clone = suggestion_mgr.clone();
         let main_input_clone = main_input.clone();
+        let output_panel_clone = output_panel.clone();
+        let action_panel_clone = action_panel.clone();
         let selection_model_clone = selection_model.clone();
         let list_view_clone = list_view.clone();
         let suggestion_list_scrollable_clone = suggestion_list_scrollable.clone();
-        key_controller.connect_key_pressed(move |_, key, _, _| {
+        key_controller.connect_key_pressed(move |_, key, _, modifiers| {
             dbg!("key_controller.connect_key_pressed");
             dbg!(&key);
+            let selected_id = || {
+                selection_model_clone
+                    .selected_item()
+                    .and_downcast::<SuggestionRowData>()
+                    .map(|it| it.id())
+            };
+
+            if action_panel_clone.is_open() {
+                match key {
+                    Key::Escape | Key::Left => {
+                        action_panel_clone.hide();
+                        return gtk::glib::Propagation::Stop;
+                    }
+                    Key::Down => {
+                        action_panel_clone.select_next();
+                        return gtk::glib::Propagation::Stop;
+                    }
+                    Key::Up => {
+                        action_panel_clone.select_previous();
+                        return gtk::glib::Propagation::Stop;
+                    }
+                    _ => (),
+                }
+            }
+
             match key {
+                Key::Return | Key::KP_Enter => {
+                    let action_index = action_index_for_modifiers(modifiers);
+                    if action_index == 0 || action_panel_clone.is_open() {
+                        return gtk::glib::Propagation::Proceed;
+                    }
+                    let id = match selected_id() {
+                        Some(id) => id,
+                        None => return gtk::glib::Propagation::Stop,
+                    };
+
+                    let post_run_action = {
+                        let mgr = suggestion_mgr_clone.lock().expect("SuggestionMgr poisoned");
+                        // suggestions without that many actions don't do anything
+                        match mgr.try_get_suggestion_by_id(&id) {
+                            Some(suggestion) if action_index < suggestion.actions.len() => {
+                                mgr.run(suggestion, action_index)
+                            }
+                            _ => PostRunAction::Nothing,
+                        }
+                    };
+                    handle_post_run_action(
+                        post_run_action,
+                        &window_clone,
+                        &main_input_clone,
+                        &output_panel_clone,
+                    );
+                    return gtk::glib::Propagation::Stop;
+                }
+                Key::k | Key::Right => {
+                    let is_shortcut = key == Key::k && modifiers.contains(ModifierType::CONTROL_MASK);
+                    let is_at_input_end = key == Key::Right
+                        && main_input_clone.position() == main_input_clone.text_length() as i32;
+                    if !is_shortcut && !is_at_input_end {
+                        return gtk::glib::Propagation::Proceed;
+                    }
+
+                    if let Some(id) = selected_id() {
+                        let mgr = suggestion_mgr_clone.lock().expect("SuggestionMgr poisoned");
+                        if let Some(suggestion) = mgr.try_get_suggestion_by_id(&id) {
+                            action_panel_clone.show_actions(suggestion);
+                        }
+                    }
+                    return gtk::glib::Propagation::Stop;
+                }
                 Key::Escape => window_clone.close(),
                 Key::Tab => {
                     let selected = selection_model_clone.selected_item();
@@ -238,6 +461,8 @@ fn main() -> glib::ExitCode {
         let container = gtk::Box::new(gtk::Orientation::Vertical, 2);
         container.set_hexpand(true);
         container.append(&main_input);
+        container.append(&output_panel);
+        container.append(&action_panel);
         container.append(&suggestion_list_scrollable);
 
         window.set_child(Some(&container));
@@ -0,0 +1,105 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::PathBuf,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use inotify::{Inotify, WatchMask};
+
+use crate::fsutil;
+
+const MAX_MATCHES: usize = 8;
+const INOTIFY_BUFFER_SIZE: usize = 4096;
+
+/// Executables found in the directories of `$PATH`, by name.
+///
+/// The directories are scanned in a background thread and watched with inotify,
+/// so installing or removing a program shows up without restarting.
+#[derive(Debug)]
+pub struct PathIndex {
+    // name -> full path, the first directory in $PATH wins like in the shell
+    executables: Arc<RwLock<BTreeMap<String, PathBuf>>>,
+}
+
+impl PathIndex {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        let executables = Arc::new(RwLock::new(BTreeMap::new()));
+        let dirs: Vec<PathBuf> = match env::var("PATH") {
+            Ok(path_var) => env::split_paths(&path_var).collect(),
+            Err(_) => vec![],
+        };
+
+        let executables_clone = executables.clone();
+        thread::spawn(move || {
+            let rescan = || {
+                let index = scan(&dirs);
+                *executables_clone.write().expect("PathIndex poisoned") = index;
+                updated.store(true, Ordering::Relaxed);
+            };
+            rescan();
+
+            let mut inotify = match Inotify::init() {
+                Ok(inotify) => inotify,
+                Err(e) => {
+                    println!("unable to watch $PATH for changes: {}", e);
+                    return;
+                }
+            };
+            let mask = WatchMask::CREATE
+                | WatchMask::DELETE
+                | WatchMask::MOVED_FROM
+                | WatchMask::MOVED_TO
+                | WatchMask::ATTRIB;
+            for dir in dirs.iter().filter(|dir| dir.is_dir()) {
+                if let Err(e) = inotify.watches().add(dir, mask) {
+                    println!("unable to watch {}: {}", dir.to_string_lossy(), e);
+                }
+            }
+
+            // package managers touch lots of files at once, every batch of
+            // events read together only causes one rescan
+            let mut buffer = [0; INOTIFY_BUFFER_SIZE];
+            while inotify.read_events_blocking(&mut buffer).is_ok() {
+                rescan();
+            }
+        });
+
+        Self { executables }
+    }
+
+    /// Executables whose name starts with `prefix`, sorted by name
+    pub fn matching(&self, prefix: &str) -> Vec<(String, PathBuf)> {
+        let executables = self.executables.read().expect("PathIndex poisoned");
+        executables
+            .range(prefix.to_string()..)
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .take(MAX_MATCHES)
+            .map(|(name, path)| (name.clone(), path.clone()))
+            .collect()
+    }
+}
+
+fn scan(dirs: &[PathBuf]) -> BTreeMap<String, PathBuf> {
+    let mut index = BTreeMap::new();
+    for dir in dirs {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !index.contains_key(&name) && fsutil::is_executable(&path) {
+                index.insert(name, path);
+            }
+        }
+    }
+
+    index
+}
@@ -0,0 +1,348 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use crate::sysaction;
+
+const PROC_DIR: &str = "/proc";
+const PASSWD_FILE: &str = "/etc/passwd";
+const TCP_TABLES: [&str; 2] = ["/proc/net/tcp", "/proc/net/tcp6"];
+const TCP_STATE_LISTEN: &str = "0A";
+// used when sysconf can't tell, the value of USER_HZ on most architectures
+const DEFAULT_CLOCK_TICKS_PER_SECOND: f32 = 100.0;
+// shorter intervals than this make the usage jump around while typing
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Term,
+    Kill,
+    Stop,
+    Cont,
+}
+
+impl Signal {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().trim_start_matches("sig") {
+            "term" => Some(Signal::Term),
+            "kill" => Some(Signal::Kill),
+            "stop" => Some(Signal::Stop),
+            "cont" => Some(Signal::Cont),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Term => "SIGTERM",
+            Signal::Kill => "SIGKILL",
+            Signal::Stop => "SIGSTOP",
+            Signal::Cont => "SIGCONT",
+        }
+    }
+
+    fn number(&self) -> i32 {
+        match self {
+            Signal::Term => libc::SIGTERM,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Stop => libc::SIGSTOP,
+            Signal::Cont => libc::SIGCONT,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Process {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+    pub uid: u32,
+    pub user: String,
+    // percentage of one core
+    pub cpu: f32,
+    pub rss: u64,
+    // only readable for processes of the same user
+    pub exe: Option<PathBuf>,
+    pub stopped: bool,
+}
+
+// what a port listener turned out to be
+#[derive(Debug, Clone)]
+pub enum PortOwner {
+    Process(Process),
+    // the socket exists but its process is not visible to this user
+    Hidden,
+}
+
+#[derive(Debug)]
+struct CpuSample {
+    taken_at: Instant,
+    ticks: u64,
+    cpu: f32,
+}
+
+/// Reads processes from /proc and sends them signals, processes of other users
+/// are signaled through pkexec
+#[derive(Debug)]
+pub struct ProcessMgr {
+    uid: u32,
+    users: HashMap<u32, String>,
+    cpu_samples: RefCell<HashMap<u32, CpuSample>>,
+}
+
+impl ProcessMgr {
+    pub fn new() -> Self {
+        Self {
+            uid: unsafe { libc::getuid() },
+            users: load_users(),
+            cpu_samples: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn processes(&self) -> Vec<Process> {
+        let entries = match fs::read_dir(PROC_DIR) {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("unable to list processes: {}", e);
+                return vec![];
+            }
+        };
+
+        let uptime = read_uptime().unwrap_or_default();
+        let processes: Vec<Process> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| self.read_process(pid, uptime))
+            .collect();
+
+        // forget the ones that are gone
+        let alive: HashSet<u32> = processes.iter().map(|it| it.pid).collect();
+        self.cpu_samples
+            .borrow_mut()
+            .retain(|pid, _| alive.contains(pid));
+        processes
+    }
+
+    pub fn process(&self, pid: u32) -> Option<Process> {
+        self.read_process(pid, read_uptime().unwrap_or_default())
+    }
+
+    pub fn is_own(&self, process: &Process) -> bool {
+        process.uid == self.uid
+    }
+
+    /// Whoever is listening on the TCP port, over IPv4 or IPv6
+    pub fn port_owners(&self, port: u16) -> Vec<PortOwner> {
+        let inodes = listening_inodes(port);
+        if inodes.is_empty() {
+            return vec![];
+        }
+
+        let mut found: HashSet<u64> = HashSet::new();
+        let mut owners: Vec<PortOwner> = self
+            .processes()
+            .into_iter()
+            .filter(|it| {
+                let sockets = socket_inodes(it.pid);
+                let matching: Vec<u64> = sockets.intersection(&inodes).copied().collect();
+                found.extend(&matching);
+                !matching.is_empty()
+            })
+            .map(PortOwner::Process)
+            .collect();
+
+        if found.len() < inodes.len() {
+            owners.push(PortOwner::Hidden);
+        }
+        owners
+    }
+
+    pub fn signal(&self, pid: u32, signal: Signal) {
+        let process = match self.process(pid) {
+            Some(process) => process,
+            None => {
+                println!("unable to signal {}, process is gone", pid);
+                return;
+            }
+        };
+
+        if !self.is_own(&process) {
+            sysaction::try_run(&vec![
+                "pkexec".to_owned(),
+                "kill".to_owned(),
+                "-s".to_owned(),
+                signal.name().trim_start_matches("SIG").to_owned(),
+                pid.to_string(),
+            ]);
+            return;
+        }
+
+        if unsafe { libc::kill(pid as libc::pid_t, signal.number()) } != 0 {
+            println!(
+                "unable to send {} to {}: {}",
+                signal.name(),
+                pid,
+                io::Error::last_os_error()
+            );
+        }
+    }
+
+    fn read_process(&self, pid: u32, uptime: f32) -> Option<Process> {
+        let dir = Path::new(PROC_DIR).join(pid.to_string());
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        let status = fs::read_to_string(dir.join("status")).ok()?;
+
+        // the name is in parenthesis and may have spaces itself
+        let (comm, fields) = stat.split_once(" (")?.1.rsplit_once(") ")?;
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let ticks = fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+        let started_at = fields.get(19)?.parse::<f32>().ok()? / clock_ticks_per_second();
+
+        let status_field = |key: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(key))
+                .and_then(|value| value.split_whitespace().next())
+        };
+        let uid = status_field("Uid:")?.parse::<u32>().ok()?;
+        // kernel threads have no memory of their own
+        let rss = status_field("VmRSS:")
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .unwrap_or(0)
+            * 1024;
+
+        let cmdline: Vec<String> = fs::read(dir.join("cmdline"))
+            .unwrap_or_default()
+            .split(|b| *b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        // comm is cut at 15 characters, the program name isn't
+        let name = cmdline
+            .first()
+            .and_then(|program| Path::new(program.split(' ').next()?).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .filter(|name| name.starts_with(comm))
+            .unwrap_or(comm.to_string());
+
+        Some(Process {
+            pid,
+            name,
+            cmdline: cmdline.join(" "),
+            uid,
+            user: self.users.get(&uid).cloned().unwrap_or(uid.to_string()),
+            cpu: self.cpu_usage(pid, ticks, uptime - started_at),
+            rss,
+            exe: fs::read_link(dir.join("exe")).ok(),
+            stopped: fields.first() == Some(&"T"),
+        })
+    }
+
+    // usage since the last sample, or since it started when there's none yet
+    fn cpu_usage(&self, pid: u32, ticks: u64, running_for: f32) -> f32 {
+        let mut samples = self.cpu_samples.borrow_mut();
+        let cpu = match samples.get(&pid) {
+            Some(sample) if sample.taken_at.elapsed() < CPU_SAMPLE_INTERVAL => return sample.cpu,
+            Some(sample) => {
+                let used = ticks.saturating_sub(sample.ticks) as f32 / clock_ticks_per_second();
+                used / sample.taken_at.elapsed().as_secs_f32() * 100.0
+            }
+            None if running_for > 0.0 => ticks as f32 / clock_ticks_per_second() / running_for * 100.0,
+            None => 0.0,
+        };
+
+        samples.insert(
+            pid,
+            CpuSample {
+                taken_at: Instant::now(),
+                ticks,
+                cpu,
+            },
+        );
+        cpu
+    }
+}
+
+fn read_uptime() -> Option<f32> {
+    fs::read_to_string(Path::new(PROC_DIR).join("uptime"))
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn load_users() -> HashMap<u32, String> {
+    fs::read_to_string(PASSWD_FILE)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+// "sl local_address rem_address st ... inode", addresses are "0100007F:1F90" with the port in hex
+fn listening_inodes(port: u16) -> HashSet<u64> {
+    TCP_TABLES
+        .iter()
+        .filter_map(|table| fs::read_to_string(table).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    let local_port = fields.get(1)?.rsplit_once(':')?.1;
+                    if u16::from_str_radix(local_port, 16).ok()? != port
+                        || *fields.get(3)? != TCP_STATE_LISTEN
+                    {
+                        return None;
+                    }
+                    fields.get(9)?.parse().ok()
+                })
+                .collect::<Vec<u64>>()
+        })
+        .collect()
+}
+
+// the fds of a socket link to "socket:[inode]"
+fn socket_inodes(pid: u32) -> HashSet<u64> {
+    let fds = match fs::read_dir(Path::new(PROC_DIR).join(pid.to_string()).join("fd")) {
+        Ok(fds) => fds,
+        Err(_) => return HashSet::new(),
+    };
+
+    fds.flatten()
+        .filter_map(|fd| {
+            let target = fs::read_link(fd.path()).ok()?;
+            target
+                .to_str()?
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+// USER_HZ, the unit the kernel reports process times in
+fn clock_ticks_per_second() -> f32 {
+    static CLOCK_TICKS_PER_SECOND: OnceLock<f32> = OnceLock::new();
+    *CLOCK_TICKS_PER_SECOND.get_or_init(|| {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 {
+            ticks as f32
+        } else {
+            DEFAULT_CLOCK_TICKS_PER_SECOND
+        }
+    })
+}
@@ -0,0 +1,214 @@
+use std::{cell::RefCell, fs, path::PathBuf};
+
+use ini::Ini;
+use regex::Regex;
+
+use crate::{config, searchengine, shellutil, urlutil};
+
+const QUICKLINKS_FILE_NAME: &str = "quicklinks.ini";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuicklinkTarget {
+    Url(String),
+    Command(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Quicklink {
+    pub name: String,
+    pub target: QuicklinkTarget,
+}
+
+#[derive(Debug, Clone)]
+pub enum QuicklinkOperation {
+    Add(Quicklink),
+    Remove(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum FilledQuicklink {
+    Url(String),
+    Command(Vec<String>),
+    // placeholders that still need a value, in order
+    Missing(Vec<String>),
+}
+
+impl Quicklink {
+    /// Builds a quicklink from what the user typed, anything that looks like a URL
+    /// (like "mailto:{to}" or "github.com/{repo}") opens one, the rest are commands.
+    /// `has_handler` tells whether a scheme that isn't well known can be opened.
+    pub fn parse(name: &str, template: &str, has_handler: impl Fn(&str) -> bool) -> Self {
+        let target = match urlutil::detect(template, has_handler) {
+            Some(detected) => QuicklinkTarget::Url(detected.url),
+            None => QuicklinkTarget::Command(template.to_string()),
+        };
+
+        Self {
+            name: name.to_string(),
+            target,
+        }
+    }
+
+    pub fn template(&self) -> &str {
+        match &self.target {
+            QuicklinkTarget::Url(url) => url,
+            QuicklinkTarget::Command(cmd) => cmd,
+        }
+    }
+
+    pub fn placeholders(&self) -> Vec<String> {
+        let placeholder_regex = Regex::new(r"\{([A-Za-z0-9_-]+)\}").unwrap();
+        let mut names: Vec<String> = Vec::new();
+        for captures in placeholder_regex.captures_iter(self.template()) {
+            let name = captures[1].to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+
+    /// Fills the placeholders with the words of `args`, in order,
+    /// the last placeholder takes the rest of the input
+    pub fn fill(&self, args: &str) -> FilledQuicklink {
+        let placeholders = self.placeholders();
+        let mut values: Vec<String> = Vec::new();
+        let mut rest = args.trim();
+        for (i, _) in placeholders.iter().enumerate() {
+            if rest.is_empty() {
+                break;
+            }
+
+            if i == placeholders.len() - 1 {
+                values.push(rest.to_string());
+                rest = "";
+            } else {
+                let (value, remaining) = rest.split_once(' ').unwrap_or((rest, ""));
+                values.push(value.to_string());
+                rest = remaining.trim_start();
+            }
+        }
+
+        if values.len() < placeholders.len() {
+            return FilledQuicklink::Missing(placeholders[values.len()..].to_vec());
+        }
+
+        let substitute = |template: &str, encode: bool| {
+            let mut result = template.to_string();
+            for (name, value) in placeholders.iter().zip(values.iter()) {
+                let value = if encode {
+                    searchengine::encode_query(value)
+                } else {
+                    value.clone()
+                };
+                result = result.replace(&format!("{{{}}}", name), &value);
+            }
+            result
+        };
+
+        match &self.target {
+            QuicklinkTarget::Url(url) => FilledQuicklink::Url(substitute(url, true)),
+            // substituting per word keeps values with spaces as a single argument,
+            // templates with unbalanced quotes are split on whitespace alone
+            QuicklinkTarget::Command(cmd) => FilledQuicklink::Command(
+                shellutil::split(cmd)
+                    .unwrap_or_else(|| cmd.split_whitespace().map(|it| it.to_string()).collect())
+                    .iter()
+                    .map(|word| substitute(word, false))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Quicklinks saved in `quicklinks.ini` in the config dir, one section per quicklink:
+///
+/// ```ini
+/// [jira]
+/// url = https://jira.internal/browse/{ticket}
+///
+/// [logs]
+/// command = journalctl --user -f -u {service}
+/// ```
+#[derive(Debug)]
+pub struct QuicklinkStore {
+    path: PathBuf,
+    links: RefCell<Vec<Quicklink>>,
+}
+
+impl QuicklinkStore {
+    pub fn load() -> Self {
+        let path = config::config_dir().join(QUICKLINKS_FILE_NAME);
+        let links = match Ini::load_from_file(&path) {
+            Ok(ini) => ini
+                .iter()
+                .filter_map(|(section, properties)| {
+                    let name = section?;
+                    let target = match (properties.get("url"), properties.get("command")) {
+                        (Some(url), _) => QuicklinkTarget::Url(url.to_string()),
+                        (None, Some(cmd)) => QuicklinkTarget::Command(cmd.to_string()),
+                        (None, None) => return None,
+                    };
+                    Some(Quicklink {
+                        name: name.to_string(),
+                        target,
+                    })
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        Self {
+            path,
+            links: RefCell::new(links),
+        }
+    }
+
+    pub fn links(&self) -> Vec<Quicklink> {
+        self.links.borrow().clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Quicklink> {
+        self.links
+            .borrow()
+            .iter()
+            .find(|link| link.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    pub fn perform(&self, op: &QuicklinkOperation) {
+        {
+            let mut links = self.links.borrow_mut();
+            match op {
+                QuicklinkOperation::Add(link) => {
+                    links.retain(|it| !it.name.eq_ignore_ascii_case(&link.name));
+                    links.push(link.clone());
+                }
+                QuicklinkOperation::Remove(name) => {
+                    links.retain(|it| !it.name.eq_ignore_ascii_case(name));
+                }
+            }
+        }
+
+        if let Err(e) = self.save() {
+            println!("unable to save quicklinks to {}: {}", self.path.to_string_lossy(), e);
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let mut ini = Ini::new();
+        for link in self.links.borrow().iter() {
+            let (key, value) = match &link.target {
+                QuicklinkTarget::Url(url) => ("url", url),
+                QuicklinkTarget::Command(cmd) => ("command", cmd),
+            };
+            ini.with_section(Some(link.name.clone())).set(key, value.clone());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        ini.write_to_file(&self.path)
+    }
+}
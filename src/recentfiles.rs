@@ -0,0 +1,179 @@
+use std::{
+    cmp::Reverse,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+use chrono::{DateTime, Utc};
+use inotify::{Inotify, WatchMask};
+
+use crate::urlutil;
+
+const RECENT_FILES_NAME: &str = "recently-used.xbel";
+const BOOKMARK_NAMESPACE: &str = "http://www.freedesktop.org/standards/desktop-bookmarks";
+const MIME_NAMESPACE: &str = "http://www.freedesktop.org/standards/shared-mime-info";
+const INOTIFY_BUFFER_SIZE: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct RecentFile {
+    pub path: String,
+    pub mime_type: Option<String>,
+    // the application that used the file last
+    pub application: Option<String>,
+    pub time: DateTime<Utc>,
+}
+
+impl RecentFile {
+    pub fn name(&self) -> String {
+        Path::new(&self.path)
+            .file_name()
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or(self.path.clone())
+    }
+}
+
+/// Documents recorded by GTK and most desktop apps in `recently-used.xbel`,
+/// most recent first. The list is reloaded whenever the file changes.
+#[derive(Debug)]
+pub struct RecentFiles {
+    files: Arc<RwLock<Vec<RecentFile>>>,
+}
+
+impl RecentFiles {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        let files = Arc::new(RwLock::new(vec![]));
+        let path = recent_files_path();
+
+        let files_clone = files.clone();
+        thread::spawn(move || {
+            let reload = || {
+                let recent = match fs::read_to_string(&path) {
+                    Ok(content) => parse(&content),
+                    Err(_) => vec![],
+                };
+                *files_clone.write().expect("RecentFiles poisoned") = recent;
+                updated.store(true, Ordering::Relaxed);
+            };
+            reload();
+
+            let dir = match path.parent() {
+                Some(dir) => dir,
+                None => return,
+            };
+            let mut inotify = match Inotify::init() {
+                Ok(inotify) => inotify,
+                Err(e) => {
+                    println!("unable to watch recent files for changes: {}", e);
+                    return;
+                }
+            };
+            // GTK replaces the file instead of writing to it, so the directory is watched
+            let mask = WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE;
+            if let Err(e) = inotify.watches().add(dir, mask) {
+                println!("unable to watch {}: {}", dir.to_string_lossy(), e);
+                return;
+            }
+
+            let mut buffer = [0; INOTIFY_BUFFER_SIZE];
+            while let Ok(events) = inotify.read_events_blocking(&mut buffer) {
+                let changed = events
+                    .into_iter()
+                    .any(|e| e.name.is_some_and(|name| name == RECENT_FILES_NAME));
+                if changed {
+                    reload();
+                }
+            }
+        });
+
+        Self { files }
+    }
+
+    /// Recent files with the query in their name, all of them when it's empty
+    pub fn matching(&self, query: &str, limit: usize) -> Vec<RecentFile> {
+        let query = query.trim().to_lowercase();
+        self.files
+            .read()
+            .expect("RecentFiles poisoned")
+            .iter()
+            .filter(|file| file.name().to_lowercase().contains(&query))
+            // the list could be outdated if files were removed after it was loaded
+            .filter(|file| Path::new(&file.path).exists())
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+fn recent_files_path() -> PathBuf {
+    let data_home = match env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home_path = env::var("HOME").expect("expected $HOME to always be defined");
+            PathBuf::from(home_path).join(".local/share")
+        }
+    };
+
+    data_home.join(RECENT_FILES_NAME)
+}
+
+fn parse_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|it| it.with_timezone(&Utc))
+}
+
+// only local files that still exist are kept
+fn parse(content: &str) -> Vec<RecentFile> {
+    let document = match roxmltree::Document::parse(content) {
+        Ok(document) => document,
+        Err(e) => {
+            println!("unable to parse {}: {}", RECENT_FILES_NAME, e);
+            return vec![];
+        }
+    };
+
+    let mut files: Vec<RecentFile> = document
+        .root_element()
+        .children()
+        .filter(|node| node.has_tag_name("bookmark"))
+        .filter_map(|bookmark| {
+            let path = urlutil::file_url_path(bookmark.attribute("href")?)?;
+            if !Path::new(&path).exists() {
+                return None;
+            }
+
+            let time = ["visited", "modified", "added"]
+                .iter()
+                .filter_map(|attribute| parse_time(bookmark.attribute(*attribute)))
+                .max()?;
+
+            let mime_type = bookmark
+                .descendants()
+                .find(|node| node.has_tag_name((MIME_NAMESPACE, "mime-type")))
+                .and_then(|node| node.attribute("type"))
+                .map(|it| it.to_string());
+
+            let application = bookmark
+                .descendants()
+                .filter(|node| node.has_tag_name((BOOKMARK_NAMESPACE, "application")))
+                .max_by_key(|node| parse_time(node.attribute("modified")))
+                .and_then(|node| node.attribute("name"))
+                .map(|it| it.to_string());
+
+            Some(RecentFile {
+                path,
+                mime_type,
+                application,
+                time,
+            })
+        })
+        .collect();
+
+    files.sort_by_key(|it| Reverse(it.time));
+    files
+}
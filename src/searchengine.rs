@@ -0,0 +1,73 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+
+pub const QUERY_PLACEHOLDER: &str = "{query}";
+pub const DEFAULT_SEARCH_ENGINE: &str = "brave";
+
+// RFC 3986 unreserved characters are kept as they are
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Debug, Clone)]
+pub struct SearchEngine {
+    // typed before the query to search with this engine, like "gh rust gtk4"
+    pub keyword: String,
+    pub name: String,
+    pub url_template: String,
+}
+
+impl SearchEngine {
+    pub fn new(keyword: &str, name: &str, url_template: &str) -> Self {
+        Self {
+            keyword: keyword.to_string(),
+            name: name.to_string(),
+            url_template: url_template.to_string(),
+        }
+    }
+
+    pub fn url(&self, query: &str) -> String {
+        self.url_template.replace(QUERY_PLACEHOLDER, &encode_query(query))
+    }
+}
+
+/// Percent-encodes a value to be placed inside a URL
+pub fn encode_query(query: &str) -> String {
+    utf8_percent_encode(query, QUERY_ENCODE_SET).to_string()
+}
+
+pub fn builtin_engines() -> Vec<SearchEngine> {
+    vec![
+        SearchEngine::new(
+            "brave",
+            "Brave",
+            "https://search.brave.com/search?source=desktop&q={query}",
+        ),
+        SearchEngine::new("ddg", "DuckDuckGo", "https://duckduckgo.com/?q={query}"),
+        SearchEngine::new("g", "Google", "https://www.google.com/search?q={query}"),
+        SearchEngine::new("gh", "GitHub", "https://github.com/search?q={query}"),
+        SearchEngine::new(
+            "wiki",
+            "Wikipedia",
+            "https://en.wikipedia.org/wiki/Special:Search?search={query}",
+        ),
+    ]
+}
+
+/// Finds the engine for inputs like "gh rust gtk4", returning it with the query part
+pub fn match_keyword<'a, 'b>(
+    engines: &'a [SearchEngine],
+    input: &'b str,
+) -> Option<(&'a SearchEngine, &'b str)> {
+    let (keyword, query) = input.split_once(' ')?;
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    engines
+        .iter()
+        .find(|engine| engine.keyword.eq_ignore_ascii_case(keyword))
+        .map(|engine| (engine, query))
+}
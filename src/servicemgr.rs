@@ -0,0 +1,520 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use zbus::{blocking::Connection, proxy, zvariant::OwnedObjectPath};
+
+// listing unit files reads the whole disk config, so it's not done on every key press
+const UNIT_LIST_TTL: Duration = Duration::from_secs(5);
+// unit files of these types are listed even when not loaded, so they can be started
+const STARTABLE_UNIT_SUFFIXES: [&str; 3] = [".service", ".timer", ".socket"];
+const JOB_MODE: &str = "replace";
+
+// name, description, load state, active state, sub state, followed unit, object path,
+// job id, job type and job object path
+type UnitStatus = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+// unit file, type of change and destination
+type UnitFileChange = (String, String, String);
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1",
+    gen_async = false,
+    blocking_name = "SystemdManagerProxy"
+)]
+trait SystemdManager {
+    fn list_units(&self) -> zbus::Result<Vec<UnitStatus>>;
+
+    // unit file path and its state, like "enabled"
+    fn list_unit_files(&self) -> zbus::Result<Vec<(String, String)>>;
+
+    // on the system bus these go through polkit, which may ask for a password
+    #[zbus(allow_interactive_auth)]
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(allow_interactive_auth)]
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(allow_interactive_auth)]
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(allow_interactive_auth)]
+    fn enable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, Vec<UnitFileChange>)>;
+
+    #[zbus(allow_interactive_auth)]
+    fn disable_unit_files(&self, files: &[&str], runtime: bool) -> zbus::Result<Vec<UnitFileChange>>;
+
+    #[zbus(allow_interactive_auth)]
+    fn reload(&self) -> zbus::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitBus {
+    User,
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitOperation {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub bus: UnitBus,
+    pub description: String,
+    // "active", "inactive", "failed"...
+    pub active_state: String,
+    // "running", "exited", "dead"...
+    pub sub_state: String,
+    // "enabled", "disabled", "static"... for units that have a file
+    pub file_state: Option<String>,
+}
+
+impl Unit {
+    pub fn is_active(&self) -> bool {
+        self.active_state == "active" || self.active_state == "reloading"
+    }
+
+    /// The command to page through its latest logs in a terminal
+    pub fn journal_cmd(&self) -> String {
+        let user_flag = match self.bus {
+            UnitBus::User => "--user ",
+            UnitBus::System => "",
+        };
+        format!(
+            "journalctl {}-e -u {}",
+            user_flag,
+            shell_words::quote(&self.name)
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct UnitList {
+    units: Vec<Unit>,
+    // when the units were listed, None once they are known to be stale
+    listed_at: Option<Instant>,
+    refreshing: bool,
+}
+
+/// Lists and controls systemd units of the user and system instances over D-Bus.
+///
+/// Both happen in background threads, listing is slow and operations on system units
+/// wait for polkit to ask for a password, `updated` is set when they are done.
+#[derive(Debug)]
+pub struct ServiceMgr {
+    user: Option<Connection>,
+    system: Option<Connection>,
+    list: Arc<Mutex<UnitList>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl ServiceMgr {
+    // no systemd just means there are no units to show
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        Self::with_connections(Connection::session().ok(), Connection::system().ok(), updated)
+    }
+
+    fn with_connections(
+        user: Option<Connection>,
+        system: Option<Connection>,
+        updated: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            user,
+            system,
+            list: Arc::new(Mutex::new(UnitList::default())),
+            updated,
+        }
+    }
+
+    /// The last listed units, a refresh is started when they are older than the TTL
+    pub fn units(&self) -> Vec<Unit> {
+        let mut list = self.list.lock().expect("ServiceMgr poisoned");
+        let is_stale = list
+            .listed_at
+            .is_none_or(|listed_at| listed_at.elapsed() >= UNIT_LIST_TTL);
+        if is_stale && !list.refreshing && (self.user.is_some() || self.system.is_some()) {
+            list.refreshing = true;
+            self.refresh_in_background();
+        }
+        list.units.clone()
+    }
+
+    pub fn perform(&self, unit: &Unit, op: UnitOperation) {
+        let connection = self.connection(unit.bus).cloned();
+        let unit = unit.clone();
+        let list = self.list.clone();
+        let updated = self.updated.clone();
+        thread::spawn(move || {
+            let result = match &connection {
+                Some(connection) => perform_operation(connection, &unit.name, op),
+                None => Err(zbus::Error::Failure(format!("no {:?} bus", unit.bus))),
+            };
+            if let Err(e) = result {
+                println!("unable to {:?} {}: {}", op, unit.name, e);
+            }
+
+            // the states changed, the next search lists them again
+            list.lock().expect("ServiceMgr poisoned").listed_at = None;
+            updated.store(true, Ordering::Relaxed);
+        });
+    }
+
+    fn connection(&self, bus: UnitBus) -> Option<&Connection> {
+        match bus {
+            UnitBus::User => self.user.as_ref(),
+            UnitBus::System => self.system.as_ref(),
+        }
+    }
+
+    fn refresh_in_background(&self) {
+        let connections: Vec<(UnitBus, Connection)> = [UnitBus::User, UnitBus::System]
+            .into_iter()
+            .filter_map(|bus| Some((bus, self.connection(bus)?.clone())))
+            .collect();
+        let list = self.list.clone();
+        let updated = self.updated.clone();
+        thread::spawn(move || {
+            let mut units = vec![];
+            for (bus, connection) in &connections {
+                match list_units(connection, *bus) {
+                    Ok(mut listed) => units.append(&mut listed),
+                    Err(e) => println!("unable to list {:?} units: {}", bus, e),
+                }
+            }
+
+            let mut list = list.lock().expect("ServiceMgr poisoned");
+            list.listed_at = Some(Instant::now());
+            list.refreshing = false;
+            if list.units != units {
+                list.units = units;
+                updated.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+fn perform_operation(connection: &Connection, name: &str, op: UnitOperation) -> zbus::Result<()> {
+    let manager = SystemdManagerProxy::new(connection)?;
+    match op {
+        UnitOperation::Start => manager.start_unit(name, JOB_MODE).map(|_| ()),
+        UnitOperation::Stop => manager.stop_unit(name, JOB_MODE).map(|_| ()),
+        UnitOperation::Restart => manager.restart_unit(name, JOB_MODE).map(|_| ()),
+        // systemctl reloads after changing unit files too
+        UnitOperation::Enable => {
+            manager.enable_unit_files(&[name], false, false)?;
+            manager.reload()
+        }
+        UnitOperation::Disable => {
+            manager.disable_unit_files(&[name], false)?;
+            manager.reload()
+        }
+    }
+}
+
+// loaded units, plus the unit files that could be started but aren't loaded
+fn list_units(connection: &Connection, bus: UnitBus) -> zbus::Result<Vec<Unit>> {
+    let manager = SystemdManagerProxy::new(connection)?;
+    let mut file_states: HashMap<String, String> = manager
+        .list_unit_files()?
+        .into_iter()
+        .filter_map(|(path, state)| {
+            let name = Path::new(&path).file_name()?.to_string_lossy().to_string();
+            Some((name, state))
+        })
+        .collect();
+
+    let mut units: Vec<Unit> = manager
+        .list_units()?
+        .into_iter()
+        .filter(|(_, _, load_state, ..)| load_state != "not-found")
+        .map(|(name, description, _, active_state, sub_state, ..)| Unit {
+            file_state: file_states.remove(&name),
+            name,
+            bus,
+            description,
+            active_state,
+            sub_state,
+        })
+        .collect();
+
+    // templates need an instance name to be started
+    units.extend(
+        file_states
+            .into_iter()
+            .filter(|(name, _)| {
+                STARTABLE_UNIT_SUFFIXES.iter().any(|it| name.ends_with(it))
+                    && !name.contains("@.")
+            })
+            .map(|(name, state)| Unit {
+                name,
+                bus,
+                description: String::new(),
+                active_state: "inactive".to_owned(),
+                sub_state: "dead".to_owned(),
+                file_state: Some(state),
+            }),
+    );
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(units)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::net::UnixStream;
+
+    use zbus::{Guid, blocking::connection, interface};
+
+    use super::*;
+
+    const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    // answers like org.freedesktop.systemd1.Manager, recording the calls that change something
+    struct FakeManager {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[interface(name = "org.freedesktop.systemd1.Manager")]
+    impl FakeManager {
+        fn list_units(&self) -> Vec<UnitStatus> {
+            let path = OwnedObjectPath::try_from("/org/freedesktop/systemd1/unit/x").unwrap();
+            let unit = |name: &str, description: &str, load_state: &str, active_state: &str, sub_state: &str| {
+                (
+                    name.to_owned(),
+                    description.to_owned(),
+                    load_state.to_owned(),
+                    active_state.to_owned(),
+                    sub_state.to_owned(),
+                    String::new(),
+                    path.clone(),
+                    0,
+                    String::new(),
+                    path.clone(),
+                )
+            };
+            vec![
+                unit("postgres.service", "PostgreSQL", "loaded", "active", "running"),
+                unit("backup.timer", "Nightly backup", "loaded", "inactive", "dead"),
+                unit("removed.service", "", "not-found", "inactive", "dead"),
+            ]
+        }
+
+        fn list_unit_files(&self) -> Vec<(String, String)> {
+            [
+                ("/etc/systemd/user/postgres.service", "enabled"),
+                ("/usr/lib/systemd/user/syncthing.service", "disabled"),
+                ("/usr/lib/systemd/user/getty@.service", "static"),
+                ("/usr/lib/systemd/user/default.target", "static"),
+            ]
+            .iter()
+            .map(|(path, state)| (path.to_string(), state.to_string()))
+            .collect()
+        }
+
+        fn start_unit(&self, name: &str, mode: &str) -> OwnedObjectPath {
+            self.record(format!("start {} {}", name, mode))
+        }
+
+        fn stop_unit(&self, name: &str, mode: &str) -> OwnedObjectPath {
+            self.record(format!("stop {} {}", name, mode))
+        }
+
+        fn restart_unit(&self, name: &str, mode: &str) -> OwnedObjectPath {
+            self.record(format!("restart {} {}", name, mode))
+        }
+
+        fn enable_unit_files(
+            &self,
+            files: Vec<String>,
+            runtime: bool,
+            force: bool,
+        ) -> (bool, Vec<UnitFileChange>) {
+            self.record(format!("enable {} {} {}", files.join(" "), runtime, force));
+            (true, vec![])
+        }
+
+        fn disable_unit_files(&self, files: Vec<String>, runtime: bool) -> Vec<UnitFileChange> {
+            self.record(format!("disable {} {}", files.join(" "), runtime));
+            vec![]
+        }
+
+        fn reload(&self) {
+            self.record("reload".to_owned());
+        }
+    }
+
+    impl FakeManager {
+        fn record(&self, call: String) -> OwnedObjectPath {
+            self.calls.lock().unwrap().push(call);
+            OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/1").unwrap()
+        }
+    }
+
+    // a private bus with only the fake manager on it, the server is kept alive with the client
+    fn fake_systemd() -> (Connection, Connection, Arc<Mutex<Vec<String>>>) {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let (server_stream, client_stream) = UnixStream::pair().unwrap();
+
+        let manager = FakeManager { calls: calls.clone() };
+        let server = thread::spawn(move || {
+            connection::Builder::unix_stream(server_stream)
+                .server(Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at("/org/freedesktop/systemd1", manager)
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        let client = connection::Builder::unix_stream(client_stream)
+            .p2p()
+            .build()
+            .unwrap();
+
+        (client, server.join().unwrap(), calls)
+    }
+
+    fn wait_for_update(updated: &AtomicBool) {
+        let started_at = Instant::now();
+        while !updated.swap(false, Ordering::Relaxed) {
+            assert!(started_at.elapsed() < WAIT_TIMEOUT, "background work never finished");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn unit(name: &str, bus: UnitBus) -> Unit {
+        Unit {
+            name: name.to_owned(),
+            bus,
+            description: String::new(),
+            active_state: "inactive".to_owned(),
+            sub_state: "dead".to_owned(),
+            file_state: None,
+        }
+    }
+
+    #[test]
+    fn list_units_adds_startable_unit_files_that_are_not_loaded() {
+        let (client, _server, _) = fake_systemd();
+
+        let units = list_units(&client, UnitBus::User).unwrap();
+
+        let names: Vec<&str> = units.iter().map(|it| it.name.as_str()).collect();
+        assert_eq!(names, vec!["backup.timer", "postgres.service", "syncthing.service"]);
+        assert_eq!(
+            units[1],
+            Unit {
+                name: "postgres.service".to_owned(),
+                bus: UnitBus::User,
+                description: "PostgreSQL".to_owned(),
+                active_state: "active".to_owned(),
+                sub_state: "running".to_owned(),
+                file_state: Some("enabled".to_owned()),
+            }
+        );
+        assert_eq!(units[0].file_state, None);
+        assert_eq!(units[2].file_state.as_deref(), Some("disabled"));
+        assert!(!units[2].is_active());
+    }
+
+    #[test]
+    fn units_are_listed_in_the_background() {
+        let (client, _server, _) = fake_systemd();
+        let updated = Arc::new(AtomicBool::new(false));
+        let service_mgr = ServiceMgr::with_connections(Some(client), None, updated.clone());
+
+        assert!(service_mgr.units().is_empty());
+        wait_for_update(&updated);
+
+        let units = service_mgr.units();
+        assert_eq!(units.len(), 3);
+        assert!(units.iter().all(|it| it.bus == UnitBus::User));
+    }
+
+    #[test]
+    fn perform_calls_the_manager_in_the_background() {
+        let (client, _server, calls) = fake_systemd();
+        let updated = Arc::new(AtomicBool::new(false));
+        let service_mgr = ServiceMgr::with_connections(Some(client), None, updated.clone());
+
+        let operations = [
+            UnitOperation::Start,
+            UnitOperation::Stop,
+            UnitOperation::Restart,
+            UnitOperation::Enable,
+            UnitOperation::Disable,
+        ];
+        for op in operations {
+            service_mgr.perform(&unit("syncthing.service", UnitBus::User), op);
+            wait_for_update(&updated);
+        }
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "start syncthing.service replace",
+                "stop syncthing.service replace",
+                "restart syncthing.service replace",
+                "enable syncthing.service false false",
+                "reload",
+                "disable syncthing.service false",
+                "reload",
+            ]
+        );
+    }
+
+    #[test]
+    fn perform_on_a_missing_bus_still_reports_an_update() {
+        let (client, _server, calls) = fake_systemd();
+        let updated = Arc::new(AtomicBool::new(false));
+        let service_mgr = ServiceMgr::with_connections(Some(client), None, updated.clone());
+
+        service_mgr.perform(&unit("sshd.service", UnitBus::System), UnitOperation::Start);
+        wait_for_update(&updated);
+
+        assert!(calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn journal_cmd_quotes_the_unit_name() {
+        assert_eq!(
+            unit("postgres.service", UnitBus::System).journal_cmd(),
+            "journalctl -e -u postgres.service"
+        );
+        assert_eq!(
+            unit("my app.service", UnitBus::User).journal_cmd(),
+            "journalctl --user -e -u 'my app.service'"
+        );
+    }
+}
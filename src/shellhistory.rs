@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::fsutil;
+
+const MAX_MATCHES: usize = 5;
+
+const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+// zsh escapes some bytes in the history file by prefixing them with this one
+// and flipping their 6th bit
+const ZSH_META: u8 = 0x83;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HistoryFormat {
+    // one command per line, optionally preceded by a `#<timestamp>` line
+    Bash,
+    // `: <timestamp>:<duration>;<command>`, multi-line commands end lines with `\`
+    ZshExtended,
+    // YAML-like entries, `- cmd: <command>` followed by `  when: <timestamp>`
+    Fish,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HistoryLine {
+    command: String,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub command: String,
+    pub count: usize,
+    score: f64,
+}
+
+/// Commands from the user's shell history, deduplicated and ranked by
+/// how recently and how often they were used.
+///
+/// Files are read in a background thread, the path comes from the config,
+/// `$HISTFILE`, or the default locations of bash, zsh and fish.
+#[derive(Debug)]
+pub struct ShellHistory {
+    entries: Arc<RwLock<Vec<HistoryEntry>>>,
+}
+
+impl ShellHistory {
+    pub fn load(files: &[String], updated: Arc<AtomicBool>) -> Self {
+        let entries = Arc::new(RwLock::new(vec![]));
+        let paths = history_paths(files);
+
+        let entries_clone = entries.clone();
+        thread::spawn(move || {
+            let mut lines: Vec<HistoryLine> = Vec::new();
+            for path in paths {
+                let content = match fs::read(&path) {
+                    Ok(content) => content,
+                    Err(_) => continue,
+                };
+                let format = detect_format(&path, &content);
+                lines.append(&mut parse(format, &content));
+            }
+
+            *entries_clone.write().expect("ShellHistory poisoned") = rank(&lines, now());
+            updated.store(true, Ordering::Relaxed);
+        });
+
+        Self { entries }
+    }
+
+    /// Best ranked commands containing the input, the ones starting with it first
+    pub fn matching(&self, input: &str) -> Vec<HistoryEntry> {
+        let input = input.trim().to_lowercase();
+        let entries = self.entries.read().expect("ShellHistory poisoned");
+        let mut matches: Vec<&HistoryEntry> = entries
+            .iter()
+            .filter(|entry| entry.command.to_lowercase().contains(&input))
+            .collect();
+        matches.sort_by_key(|entry| !entry.command.to_lowercase().starts_with(&input));
+
+        matches.into_iter().take(MAX_MATCHES).cloned().collect()
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|it| it.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn history_paths(files: &[String]) -> Vec<PathBuf> {
+    if !files.is_empty() {
+        return files.iter().map(|it| PathBuf::from(fsutil::expand_home(it))).collect();
+    }
+
+    if let Ok(histfile) = env::var("HISTFILE")
+        && !histfile.is_empty()
+    {
+        return vec![PathBuf::from(fsutil::expand_home(&histfile))];
+    }
+
+    let home = PathBuf::from(env::var("HOME").expect("expected $HOME to always be defined"));
+    let data_home = match env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".local/share"),
+    };
+
+    vec![
+        home.join(".bash_history"),
+        home.join(".zsh_history"),
+        home.join(".histfile"),
+        data_home.join("fish/fish_history"),
+    ]
+}
+
+fn detect_format(path: &Path, content: &[u8]) -> HistoryFormat {
+    let is_fish = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with("fish_history"));
+    if is_fish {
+        HistoryFormat::Fish
+    } else if content.starts_with(b": ") {
+        HistoryFormat::ZshExtended
+    } else {
+        HistoryFormat::Bash
+    }
+}
+
+fn parse(format: HistoryFormat, content: &[u8]) -> Vec<HistoryLine> {
+    match format {
+        HistoryFormat::Bash => parse_bash(&String::from_utf8_lossy(content)),
+        HistoryFormat::ZshExtended => parse_zsh(&String::from_utf8_lossy(&unmetafy(content))),
+        HistoryFormat::Fish => parse_fish(&String::from_utf8_lossy(content)),
+    }
+}
+
+fn unmetafy(content: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(content.len());
+    let mut bytes = content.iter();
+    while let Some(&b) = bytes.next() {
+        if b == ZSH_META {
+            if let Some(&next) = bytes.next() {
+                result.push(next ^ 32);
+            }
+        } else {
+            result.push(b);
+        }
+    }
+
+    result
+}
+
+fn parse_bash(content: &str) -> Vec<HistoryLine> {
+    let mut lines = Vec::new();
+    let mut time = None;
+    for line in content.lines() {
+        if let Some(timestamp) = line.strip_prefix('#').and_then(|it| it.parse::<i64>().ok()) {
+            time = Some(timestamp);
+            continue;
+        }
+
+        if !line.trim().is_empty() {
+            lines.push(HistoryLine {
+                command: line.to_string(),
+                time: time.take(),
+            });
+        }
+    }
+
+    lines
+}
+
+fn parse_zsh(content: &str) -> Vec<HistoryLine> {
+    let mut lines: Vec<HistoryLine> = Vec::new();
+    let mut continues = false;
+    for line in content.lines() {
+        if continues {
+            // the previous line ended with `\`, so this one is part of the same command
+            if let Some(last) = lines.last_mut() {
+                last.command.pop();
+                last.command.push('\n');
+                last.command.push_str(line);
+            }
+        } else if let Some((header, command)) = line.strip_prefix(": ").and_then(|it| it.split_once(';')) {
+            let time = header.split(':').next().and_then(|it| it.parse().ok());
+            lines.push(HistoryLine {
+                command: command.to_string(),
+                time,
+            });
+        } else if !line.trim().is_empty() {
+            lines.push(HistoryLine {
+                command: line.to_string(),
+                time: None,
+            });
+        }
+
+        continues = line.ends_with('\\');
+    }
+
+    lines
+}
+
+fn parse_fish(content: &str) -> Vec<HistoryLine> {
+    let mut lines: Vec<HistoryLine> = Vec::new();
+    for line in content.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            lines.push(HistoryLine {
+                command: unescape_fish(command),
+                time: None,
+            });
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ")
+            && let Some(last) = lines.last_mut()
+        {
+            last.time = when.trim().parse().ok();
+        }
+    }
+
+    lines
+}
+
+// fish only escapes backslashes and newlines in its history file
+fn unescape_fish(command: &str) -> String {
+    let mut result = String::with_capacity(command.len());
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+// how much a use counts depending on how long ago it happened, lines without
+// a timestamp use their position in the history instead (newer ones are last)
+fn recency_weight(line: &HistoryLine, position: usize, total: usize, now: i64) -> f64 {
+    match line.time {
+        Some(time) => {
+            let age_days = (now - time) / DAY_SECONDS;
+            match age_days {
+                ..4 => 100.0,
+                4..14 => 70.0,
+                14..31 => 50.0,
+                31..90 => 30.0,
+                _ => 10.0,
+            }
+        }
+        None => {
+            let newness = (position + 1) as f64 / total as f64;
+            if newness >= 0.95 {
+                100.0
+            } else if newness >= 0.8 {
+                70.0
+            } else if newness >= 0.5 {
+                50.0
+            } else {
+                30.0
+            }
+        }
+    }
+}
+
+fn rank(lines: &[HistoryLine], now: i64) -> Vec<HistoryEntry> {
+    let mut entries: HashMap<&str, HistoryEntry> = HashMap::new();
+    for (position, line) in lines.iter().enumerate() {
+        let command = line.command.trim();
+        if command.is_empty() {
+            continue;
+        }
+
+        let weight = recency_weight(line, position, lines.len(), now);
+        let entry = entries.entry(command).or_insert(HistoryEntry {
+            command: command.to_string(),
+            count: 0,
+            score: 0.0,
+        });
+        entry.count += 1;
+        entry.score += weight;
+    }
+
+    let mut entries: Vec<HistoryEntry> = entries.into_values().collect();
+    entries.sort_by(|a, b| b.score.total_cmp(&a.score));
+    entries
+}
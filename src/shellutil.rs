@@ -0,0 +1,113 @@
+use std::env;
+
+use regex::Regex;
+
+use crate::fsutil;
+
+const FALLBACK_SHELL: &str = "/bin/sh";
+
+// characters that mean something to the shell when they are not quoted
+const SHELL_METACHARACTERS: [char; 12] = ['|', '&', ';', '<', '>', '(', ')', '$', '`', '*', '?', '['];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunMode {
+    // executed as is, arguments split with POSIX shell word rules
+    Direct,
+    // passed to `$SHELL -c`
+    Shell,
+    // passed to `$SHELL -c` inside a terminal emulator
+    Terminal,
+}
+
+pub fn user_shell() -> String {
+    match env::var("SHELL") {
+        Ok(shell) if !shell.is_empty() => shell,
+        _ => FALLBACK_SHELL.to_owned(),
+    }
+}
+
+/// Splits the input in words following POSIX shell rules, expanding a leading `~`,
+/// fails on unbalanced quotes
+pub fn split(input: &str) -> Option<Vec<String>> {
+    let words = shell_words::split(input).ok()?;
+    if words.is_empty() {
+        return None;
+    }
+
+    Some(
+        words
+            .into_iter()
+            .map(|word| {
+                if word == "~" || word.starts_with("~/") {
+                    fsutil::expand_home(&word)
+                } else {
+                    word
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Whether the input uses syntax only a shell can interpret,
+/// like pipes, redirects, variables, globs or environment assignments
+pub fn needs_shell(input: &str) -> bool {
+    let assignment_regex = Regex::new(r"^\s*[A-Za-z_][A-Za-z0-9_]*=").unwrap();
+    if assignment_regex.is_match(input) {
+        return true;
+    }
+
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut escaped = false;
+    for c in input.chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match c {
+            '\\' if !in_single_quotes => escaped = true,
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            // variables and command substitution still expand inside double quotes
+            '$' | '`' if in_double_quotes => return true,
+            c if !in_single_quotes && !in_double_quotes && SHELL_METACHARACTERS.contains(&c) => {
+                return true;
+            }
+            _ => (),
+        }
+    }
+
+    false
+}
+
+pub fn default_run_mode(input: &str) -> RunMode {
+    if needs_shell(input) || split(input).is_none() {
+        RunMode::Shell
+    } else {
+        RunMode::Direct
+    }
+}
+
+pub fn shell_cmd(input: &str) -> Vec<String> {
+    vec![user_shell(), "-c".to_owned(), input.to_string()]
+}
+
+/// Command to run the input inside a terminal, the terminal is left with an
+/// interactive shell after it finishes so the output can be read
+pub fn terminal_cmd(terminal: &[String], input: &str) -> Vec<String> {
+    let shell = user_shell();
+    let mut cmd = terminal.to_vec();
+    cmd.push(shell.clone());
+    cmd.push("-c".to_owned());
+    cmd.push(format!("{}; exec {}", input, shell));
+    cmd
+}
+
+pub fn cmd_for_mode(mode: RunMode, input: &str, terminal: Option<&[String]>) -> Option<Vec<String>> {
+    match mode {
+        RunMode::Direct => split(input),
+        RunMode::Shell => Some(shell_cmd(input)),
+        RunMode::Terminal => terminal.map(|terminal| terminal_cmd(terminal, input)),
+    }
+}
@@ -0,0 +1,314 @@
+use std::{cell::RefCell, collections::HashMap, fs, io, path::PathBuf};
+
+use chrono::Local;
+use regex::Regex;
+
+use crate::{
+    clipboard, config,
+    sysaction::{self, TypingTool},
+};
+
+const SNIPPETS_DIR_NAME: &str = "snippets";
+const SNIPPET_EXTENSION: &str = "txt";
+const PREVIEW_MAX_CHARS: usize = 80;
+
+// filled in when the snippet is used, every other placeholder is asked to the user
+const DATE_PLACEHOLDER: &str = "date";
+const TIME_PLACEHOLDER: &str = "time";
+const CLIPBOARD_PLACEHOLDER: &str = "clipboard";
+// where the cursor is left after typing the snippet
+const CURSOR_PLACEHOLDER: &str = "cursor";
+const BUILTIN_PLACEHOLDERS: [&str; 4] = [
+    DATE_PLACEHOLDER,
+    TIME_PLACEHOLDER,
+    CLIPBOARD_PLACEHOLDER,
+    CURSOR_PLACEHOLDER,
+];
+
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    pub name: String,
+    pub template: String,
+}
+
+/// A template with a value for each of its fields, the builtin placeholders
+/// are only filled when it's used
+#[derive(Debug, Clone)]
+pub struct SnippetText {
+    template: String,
+    values: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SnippetOperation {
+    Copy(SnippetText),
+    Type(SnippetText),
+    AddFromClipboard(String),
+    Remove(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum FilledSnippet {
+    Filled(SnippetText),
+    // fields that still need a value, in order
+    Missing(Vec<String>),
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{([A-Za-z0-9_-]+)\}").unwrap()
+}
+
+impl Snippet {
+    /// Placeholders the user has to give a value for, in the order they appear
+    pub fn fields(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for captures in placeholder_regex().captures_iter(&self.template) {
+            let name = captures[1].to_string();
+            if !BUILTIN_PLACEHOLDERS.contains(&name.as_str()) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        names
+    }
+
+    /// Fills the fields with the words of `args`, in order, the last field
+    /// takes the rest of the input, the same way quicklinks do
+    pub fn fill(&self, args: &str) -> FilledSnippet {
+        let fields = self.fields();
+        let mut values: Vec<String> = Vec::new();
+        let mut rest = args.trim();
+        for (i, _) in fields.iter().enumerate() {
+            if rest.is_empty() {
+                break;
+            }
+
+            if i == fields.len() - 1 {
+                values.push(rest.to_string());
+                rest = "";
+            } else {
+                let (value, remaining) = rest.split_once(' ').unwrap_or((rest, ""));
+                values.push(value.to_string());
+                rest = remaining.trim_start();
+            }
+        }
+
+        if values.len() < fields.len() {
+            return FilledSnippet::Missing(fields[values.len()..].to_vec());
+        }
+
+        FilledSnippet::Filled(SnippetText {
+            template: self.template.clone(),
+            values: fields.into_iter().zip(values).collect(),
+        })
+    }
+
+    pub fn preview(&self) -> String {
+        preview(&self.template)
+    }
+}
+
+/// The first line of the text, marked as cut when there's more
+pub fn preview(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    let mut preview: String = line.chars().take(PREVIEW_MAX_CHARS).collect();
+    if line.chars().count() > PREVIEW_MAX_CHARS || text.trim().lines().count() > 1 {
+        preview.push('…');
+    }
+    preview
+}
+
+impl SnippetText {
+    /// The text with its fields filled, builtin placeholders are left as they are
+    pub fn preview(&self) -> String {
+        preview(&self.render(false).0)
+    }
+
+    /// The text with every placeholder replaced, and how many characters come after
+    /// `{cursor}`, so the cursor can be moved back there
+    fn expand(&self) -> (String, usize) {
+        self.render(true)
+    }
+
+    // a single pass over the template, so placeholders typed in the values stay as they are
+    fn render(&self, builtins: bool) -> (String, usize) {
+        let now = Local::now();
+        let mut clipboard: Option<String> = None;
+        let mut text = String::new();
+        // only the first cursor counts, the others are just removed
+        let mut cursor_at: Option<usize> = None;
+        let mut last = 0;
+
+        for captures in placeholder_regex().captures_iter(&self.template) {
+            let placeholder = captures.get(0).unwrap();
+            text.push_str(&self.template[last..placeholder.start()]);
+            last = placeholder.end();
+
+            let name = &captures[1];
+            if let Some(value) = self.values.get(name) {
+                text.push_str(value);
+                continue;
+            }
+            if !builtins {
+                text.push_str(placeholder.as_str());
+                continue;
+            }
+
+            match name {
+                DATE_PLACEHOLDER => text.push_str(&now.format("%Y-%m-%d").to_string()),
+                TIME_PLACEHOLDER => text.push_str(&now.format("%H:%M").to_string()),
+                CLIPBOARD_PLACEHOLDER => text.push_str(clipboard.get_or_insert_with(|| {
+                    clipboard::paste_text().unwrap_or_else(|e| {
+                        println!("unable to read the clipboard for a snippet: {}", e);
+                        String::new()
+                    })
+                })),
+                CURSOR_PLACEHOLDER => {
+                    cursor_at.get_or_insert(text.chars().count());
+                }
+                _ => text.push_str(placeholder.as_str()),
+            }
+        }
+        text.push_str(&self.template[last..]);
+
+        let cursor_offset = cursor_at.map_or(0, |at| text.chars().count() - at);
+        (text, cursor_offset)
+    }
+}
+
+/// Snippets saved as text files in the `snippets` dir of the config dir, the file
+/// name (without `.txt`) is the snippet name and its content the template:
+///
+/// ```text
+/// Hi {name},
+///
+/// {cursor}
+///
+/// Sent on {date}
+/// ```
+#[derive(Debug)]
+pub struct SnippetStore {
+    dir: PathBuf,
+    snippets: RefCell<Vec<Snippet>>,
+    // looked up once, typing is only offered when there's a tool for it
+    typing_tool: Option<TypingTool>,
+}
+
+impl SnippetStore {
+    pub fn load() -> Self {
+        let dir = config::config_dir().join(SNIPPETS_DIR_NAME);
+        let mut snippets: Vec<Snippet> = match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension()? != SNIPPET_EXTENSION {
+                        return None;
+                    }
+                    Some(Snippet {
+                        name: path.file_stem()?.to_string_lossy().to_string(),
+                        template: fs::read_to_string(&path).ok()?,
+                    })
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            dir,
+            snippets: RefCell::new(snippets),
+            typing_tool: sysaction::find_typing_tool(),
+        }
+    }
+
+    pub fn can_type(&self) -> bool {
+        self.typing_tool.is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Snippet> {
+        self.snippets
+            .borrow()
+            .iter()
+            .find(|snippet| snippet.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Snippets with the query in their name or template, name matches first
+    pub fn matching(&self, query: &str) -> Vec<Snippet> {
+        let query = query.trim().to_lowercase();
+        let snippets = self.snippets.borrow();
+        let (mut by_name, by_template): (Vec<Snippet>, Vec<Snippet>) = snippets
+            .iter()
+            .filter(|it| {
+                it.name.to_lowercase().contains(&query) || it.template.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .partition(|it| it.name.to_lowercase().contains(&query));
+
+        by_name.extend(by_template);
+        by_name
+    }
+
+    pub fn perform(&self, op: &SnippetOperation) {
+        let result = match op {
+            SnippetOperation::Copy(filled) => {
+                sysaction::set_clipboard(&filled.expand().0);
+                Ok(())
+            }
+            SnippetOperation::Type(filled) => {
+                match self.typing_tool {
+                    Some(tool) => {
+                        let (text, cursor_offset) = filled.expand();
+                        sysaction::type_text(tool, &text, cursor_offset);
+                    }
+                    None => println!("unable to type text, neither wtype, ydotool nor xdotool are installed"),
+                }
+                Ok(())
+            }
+            SnippetOperation::AddFromClipboard(name) => self.add_from_clipboard(name),
+            SnippetOperation::Remove(name) => self.remove(name),
+        };
+
+        if let Err(e) = result {
+            println!("unable to update snippets in {}: {}", self.dir.to_string_lossy(), e);
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", name, SNIPPET_EXTENSION))
+    }
+
+    fn add_from_clipboard(&self, name: &str) -> io::Result<()> {
+        let template = match clipboard::paste_text() {
+            Ok(text) if !text.trim().is_empty() => text,
+            Ok(_) => return Ok(()),
+            Err(e) => return Err(io::Error::other(e.to_string())),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(name), &template)?;
+
+        let mut snippets = self.snippets.borrow_mut();
+        snippets.retain(|it| !it.name.eq_ignore_ascii_case(name));
+        snippets.push(Snippet {
+            name: name.to_string(),
+            template,
+        });
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> io::Result<()> {
+        let snippet = match self.get(name) {
+            Some(snippet) => snippet,
+            None => return Ok(()),
+        };
+
+        fs::remove_file(self.path(&snippet.name))?;
+        self.snippets
+            .borrow_mut()
+            .retain(|it| !it.name.eq_ignore_ascii_case(name));
+        Ok(())
+    }
+}
@@ -1,29 +1,117 @@
-use std::{env, fs, path::Path, rc::Rc};
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
-use base64::prelude::*;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use derivative::Derivative;
 use freedesktop_desktop_entry::DesktopEntry;
 use gtk4::{
     gdk::{self, prelude::DisplayExt},
     glib::base64_decode,
 };
-use wl_clipboard_rs::copy::{MimeType, Options, Source};
 
 use crate::{
-    conversionutil,
+    charmap::{self, CharMap},
+    clipboard::ClipboardData,
+    clipboardhistory::{ClipEntry, ClipKind, ClipboardHistory},
+    codecutil::{self, Codec},
+    config::Config,
+    contentsearch::ContentSearch,
+    dateutil::{self, DateResult},
+    fileindex::FileIndex,
+    conversionutil, fsutil,
+    genutil::{self, PasswordOptions},
+    hashutil::{self, Algorithm, FileHashCache, FileHashState, HashDigest},
+    pathindex::PathIndex,
+    processmgr::{PortOwner, Process, ProcessMgr, Signal},
+    quicklink::{FilledQuicklink, Quicklink, QuicklinkOperation, QuicklinkStore},
+    recentfiles::{RecentFile, RecentFiles},
+    searchengine,
+    servicemgr::{ServiceMgr, Unit, UnitBus, UnitOperation},
+    shellutil::{self, RunMode},
     sessionmgr::{SessionMgr, SessionOperation},
-    sysaction::{self, find},
-    sysinfo::{DefaultApplicationType, SysInfoLoader},
+    shellhistory::ShellHistory,
+    snippet::{FilledSnippet, SnippetOperation, SnippetStore, SnippetText},
+    sysaction::{self, CapturedProcess},
+    sysinfo::{self, DefaultApplicationType, SysInfoLoader},
+    timeutil::{self, TimeQuery, Zone},
+    trashutil, urlutil,
+    windowmgr::{Window, WindowMgr},
 };
 use regex::Regex;
 
+const GENERATED_TITLE_MAX_CHARS: usize = 80;
+const QUICKLINK_COMMAND: &str = "ql";
+const MIN_HISTORY_INPUT_CHARS: usize = 2;
+const FILE_SEARCH_COMMAND: &str = "f";
+const CONTENT_SEARCH_PREFIX: &str = "in:";
+const RECENT_FILES_COMMAND: &str = "recent";
+const RENAME_COMMAND: &str = "rename";
+const RENAME_SEPARATOR: &str = " as ";
+const TRASH_COMMAND: &str = "trash";
+const RECENT_FILES_LIMIT: usize = 15;
+// recent files mixed into the normal results, above everything but URLs
+const BOOSTED_RECENT_FILES_LIMIT: usize = 3;
+const MIN_BOOSTED_RECENT_INPUT_CHARS: usize = 2;
+const CLIPBOARD_HISTORY_COMMAND: &str = "clip";
+const CLIPBOARD_HISTORY_LIMIT: usize = 30;
+const SNIPPET_COMMAND: &str = "snip";
+const EMOJI_COMMAND: &str = "emoji";
+const CHARACTER_COMMAND: &str = "char";
+const CHARACTER_LIMIT: usize = 30;
+const WINDOW_COMMAND: &str = "win";
+const PROCESS_COMMANDS: [&str; 2] = ["ps", "kill"];
+const PROCESS_PORT_PREFIX: &str = "port ";
+const PROCESS_LIMIT: usize = 30;
+const UNIT_COMMANDS: [&str; 2] = ["unit", "systemctl"];
+const UNIT_LIMIT: usize = 30;
+
 #[derive(Debug, Clone)]
 pub enum Action {
     NoOp,
     Open(DefaultApplicationType, String),
+    // a file, opened with the default app for its MIME type, which is only looked up
+    // when it runs as that takes a subprocess
+    OpenDefault(String),
+    // a text file and the line to show, for editors that support it
+    OpenAtLine(String, usize),
     Command(Vec<String>),
+    // runs the command showing its output in the launcher instead of closing it
+    CaptureOutput(Vec<String>),
     Session(SessionOperation),
     CopyToClipboard(String),
+    // copied with the hint that keeps clipboard managers from recording it
+    CopySecret(String),
+    Quicklink(QuicklinkOperation),
+    Snippet(SnippetOperation),
+    // shows the file selected in the file manager
+    Reveal(String),
+    CopyFileContents(String),
+    // formatted text and its plain version, for apps that don't take HTML
+    CopyHtml(String, String),
+    // the files themselves, to be pasted in a file manager
+    CopyFiles(Vec<String>),
+    Trash(String),
+    // path and the new file name
+    Rename(String, String),
+    // opens a terminal with the folder as its working directory
+    OpenTerminal(String),
+    // replaces the input instead of running something, so the user can keep typing
+    SetInput(String),
+    // puts an entry from the clipboard history back, with its original MIME type
+    CopyClip(ClipEntry),
+    // what gets copied and the character it's recorded as recently used
+    CopyCharacter(String, String),
+    FocusWindow(Window),
+    Signal(u32, Signal),
+    Unit(Unit, UnitOperation),
 }
 
 #[derive(Debug, Clone)]
@@ -34,54 +122,479 @@ pub struct Suggestion {
     // TODO: maybe turn this guy into an Option since not all options will have an icon
     //      (ex: command)
     pub icon_path: Option<String>,
-    pub action: Action,
+    // the first one runs on Enter, the others are alternatives picked with a
+    // modifier key or from the actions panel
+    pub actions: Vec<SuggestionAction>,
 
     pub completion: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct SuggestionAction {
+    pub label: String,
+    pub action: Action,
+}
+
+impl SuggestionAction {
+    pub fn new(label: &str, action: Action) -> Self {
+        Self {
+            label: label.to_string(),
+            action,
+        }
+    }
+}
+
+impl From<Action> for SuggestionAction {
+    fn from(action: Action) -> Self {
+        Self {
+            label: action.label(),
+            action,
+        }
+    }
+}
+
 pub enum PostRunAction {
     Nothing,
     Close,
+    ShowOutput(CapturedProcess),
+    SetInput(String),
+}
+
+fn get_jwt_suggestions(token: &str) -> Vec<Suggestion> {
+    let jwt = match codecutil::decode_jwt(token) {
+        Ok(jwt) => jwt,
+        Err(_) => return vec![],
+    };
+
+    let mut s = vec![
+        Suggestion {
+            id: "evaluation.codec.jwt.header".to_owned(),
+            title: "JWT header".to_owned(),
+            description: jwt.header.clone(),
+            icon_path: None,
+            actions: vec![Action::CopyToClipboard(jwt.header).into()],
+            completion: None,
+        },
+        Suggestion {
+            id: "evaluation.codec.jwt.payload".to_owned(),
+            title: "JWT payload".to_owned(),
+            description: jwt.payload.clone(),
+            icon_path: None,
+            actions: vec![Action::CopyToClipboard(jwt.payload).into()],
+            completion: None,
+        },
+    ];
+
+    for t in jwt.times.iter() {
+        s.push(Suggestion {
+            id: format!("evaluation.codec.jwt.{}", t.claim),
+            title: codecutil::format_claim_time(t),
+            description: String::new(),
+            icon_path: None,
+            actions: vec![Action::CopyToClipboard(t.time.to_rfc3339()).into()],
+            completion: None,
+        });
+    }
+
+    s
+}
+
+fn get_date_suggestions(result: DateResult) -> Vec<Suggestion> {
+    match result {
+        DateResult::Date(date) => {
+            let days_from_today = (date - Local::now().date_naive()).num_days();
+            let relative = match days_from_today {
+                0 => "today".to_owned(),
+                d if d > 0 => format!("in {}", dateutil::describe_days(d)),
+                d => format!("{} ago", dateutil::describe_days(-d)),
+            };
+
+            dateutil::date_formats(&date)
+                .into_iter()
+                .map(|(name, value)| Suggestion {
+                    id: format!("evaluation.date.{}", name),
+                    title: format!("{} ({})", value, date.format("%A")),
+                    description: format!("{}, {}", name, relative),
+                    icon_path: None,
+                    actions: vec![Action::CopyToClipboard(value).into()],
+                    completion: None,
+                })
+                .collect()
+        }
+        DateResult::Duration(from, to) => {
+            let days = (to - from).num_days();
+            vec![Suggestion {
+                id: "evaluation.date.duration".to_owned(),
+                title: format!("Result: {}", dateutil::describe_days(days)),
+                description: format!(
+                    "From {} to {}",
+                    from.format("%A, %Y-%m-%d"),
+                    to.format("%A, %Y-%m-%d")
+                ),
+                icon_path: None,
+                actions: vec![
+                    SuggestionAction::new("Copy days", Action::CopyToClipboard(days.to_string())),
+                    SuggestionAction::new(
+                        "Copy weeks and days",
+                        Action::CopyToClipboard(dateutil::describe_weeks(days.abs())),
+                    ),
+                    SuggestionAction::new(
+                        "Copy weeks",
+                        Action::CopyToClipboard(format!("{:.2}", days as f64 / 7.0)),
+                    ),
+                ],
+                completion: None,
+            }]
+        }
+        DateResult::Week(date) => {
+            let week = date.iso_week();
+            let monday = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon);
+            let sunday = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Sun);
+            let description = match (monday, sunday) {
+                (Some(monday), Some(sunday)) => format!(
+                    "From {} to {}",
+                    monday.format("%A, %Y-%m-%d"),
+                    sunday.format("%A, %Y-%m-%d")
+                ),
+                _ => String::new(),
+            };
+
+            let mut actions = vec![
+                SuggestionAction::new("Copy week number", Action::CopyToClipboard(week.week().to_string())),
+                SuggestionAction::new(
+                    "Copy ISO week",
+                    Action::CopyToClipboard(format!("{}-W{:02}", week.year(), week.week())),
+                ),
+            ];
+            if let Some(monday) = monday {
+                actions.push(SuggestionAction::new(
+                    "Copy Monday",
+                    Action::CopyToClipboard(monday.format("%Y-%m-%d").to_string()),
+                ));
+            }
+
+            vec![Suggestion {
+                id: "evaluation.date.week".to_owned(),
+                title: format!(
+                    "Week {} of {} ({})",
+                    week.week(),
+                    week.year(),
+                    date.format("%A, %Y-%m-%d")
+                ),
+                description,
+                icon_path: None,
+                actions,
+                completion: None,
+            }]
+        }
+    }
+}
+
+// "battery", "mem", "disk", "uptime", "load" and "ip"
+fn get_system_status_suggestions(input: &str) -> Vec<Suggestion> {
+    match input.trim().to_lowercase().as_str() {
+        "battery" | "bat" => sysinfo::read_batteries()
+            .into_iter()
+            .map(|it| {
+                let time_left = match (it.time_left, it.status.as_str()) {
+                    (Some(left), "Charging") => format!(", full in {}", describe_duration(left)),
+                    (Some(left), _) => format!(", {} left", describe_duration(left)),
+                    (None, _) => String::new(),
+                };
+                get_status_suggestion(
+                    &format!("battery.{}", it.name),
+                    format!("Battery {}%, {}{}", it.capacity, it.status.to_lowercase(), time_left),
+                    it.model.unwrap_or(it.name),
+                    format!("{}%", it.capacity),
+                )
+            })
+            .collect(),
+        "mem" | "memory" => {
+            let memory = match sysinfo::read_memory() {
+                Some(memory) => memory,
+                None => return vec![],
+            };
+
+            let used = memory.total - memory.available;
+            let mut s = vec![get_status_suggestion(
+                "memory",
+                format!(
+                    "Memory: {} used of {} ({}%)",
+                    describe_size(used),
+                    describe_size(memory.total),
+                    used * 100 / memory.total.max(1)
+                ),
+                format!("{} available", describe_size(memory.available)),
+                describe_size(used),
+            )];
+            if memory.swap_total > 0 {
+                let swap_used = memory.swap_total - memory.swap_free;
+                s.push(get_status_suggestion(
+                    "swap",
+                    format!(
+                        "Swap: {} used of {}",
+                        describe_size(swap_used),
+                        describe_size(memory.swap_total)
+                    ),
+                    format!("{} free", describe_size(memory.swap_free)),
+                    describe_size(swap_used),
+                ));
+            }
+            s
+        }
+        "disk" | "df" => sysinfo::read_disks()
+            .into_iter()
+            .map(|it| {
+                let used = it.total.saturating_sub(it.available);
+                get_status_suggestion(
+                    &format!("disk.{}", it.mount_point),
+                    format!(
+                        "{}: {} free of {} ({}% used)",
+                        it.mount_point,
+                        describe_size(it.available),
+                        describe_size(it.total),
+                        used * 100 / it.total.max(1)
+                    ),
+                    format!("{} · {}", it.device, it.fs_type),
+                    describe_size(it.available),
+                )
+            })
+            .collect(),
+        "uptime" => match sysinfo::read_uptime() {
+            Some(uptime) => {
+                let booted_at = Local::now() - uptime;
+                vec![get_status_suggestion(
+                    "uptime",
+                    format!("Up for {}", describe_duration(uptime)),
+                    format!("Since {}", booted_at.format("%Y-%m-%d %H:%M")),
+                    describe_duration(uptime),
+                )]
+            }
+            None => vec![],
+        },
+        "load" => match sysinfo::read_load() {
+            Some(load) => {
+                let cpus = std::thread::available_parallelism().map_or(1, |it| it.get());
+                let averages = format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen);
+                vec![get_status_suggestion(
+                    "load",
+                    format!("Load: {}", averages),
+                    format!(
+                        "Last 1, 5 and 15 minutes · {} CPUs · {} running of {} tasks",
+                        cpus, load.running, load.tasks
+                    ),
+                    averages,
+                )]
+            }
+            None => vec![],
+        },
+        "ip" => {
+            let mut s: Vec<Suggestion> = sysinfo::read_addresses()
+                .into_iter()
+                .map(|it| {
+                    get_status_suggestion(
+                        &format!("ip.{}.{}", it.interface, it.address),
+                        it.address.to_string(),
+                        format!(
+                            "{} · {}",
+                            it.interface,
+                            if it.address.is_ipv4() { "IPv4" } else { "IPv6" }
+                        ),
+                        it.address.to_string(),
+                    )
+                })
+                .collect();
+            s.extend(sysinfo::read_default_routes().into_iter().map(|it| {
+                get_status_suggestion(
+                    &format!("route.{}.{}", it.interface, it.gateway),
+                    format!("Default route via {}", it.gateway),
+                    it.interface,
+                    it.gateway.to_string(),
+                )
+            }));
+            s
+        }
+        _ => vec![],
+    }
 }
 
-fn get_brave_search_url(query: &str) -> String {
-    let mut url = String::new();
-    url.push_str("search.brave.com/search?source=desktop&q=");
-    url.push_str(&query.replace(" ", "+"));
+fn get_digest_suggestions(
+    mut digests: Vec<HashDigest>,
+    preferred: Option<Algorithm>,
+) -> Vec<Suggestion> {
+    if let Some(algorithm) = preferred {
+        digests.sort_by_key(|d| d.algorithm != algorithm);
+    }
 
-    url
+    digests
+        .into_iter()
+        .map(|d| Suggestion {
+            id: format!("evaluation.hash.{}", d.algorithm.id()),
+            title: format!("{}: {}", d.algorithm.name(), d.hex),
+            description: String::new(),
+            icon_path: None,
+            actions: vec![Action::CopyToClipboard(d.hex).into()],
+            completion: None,
+        })
+        .collect()
 }
 
-fn set_clipboard(value: &str) {
-    dbg!("setting cilpboard");
-    dbg!(value);
+// what a window of the app may be called by the compositor
+fn get_app_names(entry: &DesktopEntry) -> Vec<String> {
+    let mut names = vec![entry.id().to_string()];
+    if let Some(class) = entry.startup_wm_class() {
+        names.push(class.to_string());
+    }
+    if let Some(program) = SysInfoLoader::cmd(entry).first()
+        && let Some(name) = Path::new(program).file_name()
+    {
+        names.push(name.to_string_lossy().to_string());
+    }
 
-    let opts = Options::new();
-    let result = opts.copy(
-        Source::Bytes(value.to_string().into_bytes().into()),
-        MimeType::Autodetect,
-    );
+    names
+}
 
-    if let Err(e) = result {
-        println!("unable to copy to clipboard {}", e);
+fn get_character_actions(character: &str) -> Vec<SuggestionAction> {
+    vec![
+        Action::CopyCharacter(character.to_string(), character.to_string()).into(),
+        SuggestionAction::new("Copy codepoint", Action::CopyToClipboard(charmap::codepoints(character))),
+        SuggestionAction::new("Copy HTML entity", Action::CopyToClipboard(charmap::html_entity(character))),
+        SuggestionAction::new("Copy Rust escape", Action::CopyToClipboard(charmap::rust_escape(character))),
+    ]
+}
+
+fn describe_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 * 1024 * 1024 => {
+            format!("{:.1} TB", b as f64 / (1024.0 * 1024.0 * 1024.0 * 1024.0))
+        }
+        b if b >= 1024 * 1024 * 1024 => format!("{:.1} GB", b as f64 / (1024.0 * 1024.0 * 1024.0)),
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{} KB", b / 1024),
+        b => format!("{} bytes", b),
+    }
+}
+
+// the two largest units, "3 days, 4 hours" or "12 minutes"
+fn describe_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let parts = [
+        (minutes / (60 * 24), "day"),
+        (minutes / 60 % 24, "hour"),
+        (minutes % 60, "minute"),
+    ];
+
+    let described: Vec<String> = parts
+        .iter()
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{} {}{}", value, unit, if *value == 1 { "" } else { "s" }))
+        .collect();
+    if described.is_empty() {
+        return "less than a minute".to_owned();
+    }
+    described.join(", ")
+}
+
+// a row whose value gets copied
+fn get_status_suggestion(id: &str, title: String, description: String, value: String) -> Suggestion {
+    Suggestion {
+        id: format!("system.status.{}", id),
+        title,
+        description,
+        icon_path: None,
+        actions: vec![Action::CopyToClipboard(value).into()],
+        completion: None,
+    }
+}
+
+// the root and home folders, which nobody means to move to the trash
+fn is_protected_path(absolute_path: &str) -> bool {
+    let path = Path::new(absolute_path);
+    path == Path::new("/") || path == Path::new(&fsutil::expand_home("~"))
+}
+
+// web pages go to the browser, "mailto:" or "ssh://" ones to their scheme handler
+fn get_url_app_type(url: &str) -> DefaultApplicationType {
+    match urlutil::scheme(url) {
+        Some(scheme) if scheme != "http" && scheme != "https" => {
+            DefaultApplicationType::scheme_handler(&scheme)
+        }
+        _ => DefaultApplicationType::Browser,
+    }
+}
+
+fn get_search_actions(url: String, query: &str) -> Vec<SuggestionAction> {
+    vec![
+        Action::Open(DefaultApplicationType::Browser, url.clone()).into(),
+        SuggestionAction::new("Copy URL", Action::CopyToClipboard(url)),
+        SuggestionAction::new("Copy query", Action::CopyToClipboard(query.trim().to_string())),
+    ]
+}
+
+fn get_file_hash_state_suggestion(path: &str, state: &FileHashState) -> Suggestion {
+    let (title, description) = match state {
+        FileHashState::InProgress { hashed, total } => (
+            format!(
+                "Hashing '{}': {}%",
+                path,
+                if *total == 0 { 100 } else { hashed * 100 / total }
+            ),
+            format!("{} of {} bytes", hashed, total),
+        ),
+        FileHashState::Failed(e) => (format!("Unable to hash '{}'", path), e.clone()),
+        FileHashState::Done(_) => (format!("Hashed '{}'", path), String::new()),
+    };
+
+    Suggestion {
+        id: "evaluation.hash.progress".to_owned(),
+        title,
+        description,
+        icon_path: None,
+        actions: vec![Action::NoOp.into()],
+        completion: None,
     }
 }
 
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct SuggestionMgr {
+    config: Config,
     sysinfo_loader: SysInfoLoader,
     session_mgr: Rc<SessionMgr>,
+    quicklinks: QuicklinkStore,
+    snippets: SnippetStore,
+    charmap: CharMap,
+    window_mgr: WindowMgr,
+    process_mgr: ProcessMgr,
+    service_mgr: ServiceMgr,
 
     // items that don't depend on user input,
     // they are just loaded and don't change dynamically
     static_items: Vec<Suggestion>,
 
     relevant_items: Vec<Suggestion>,
+    last_input: String,
+    // random values for the last input, generated once so background refreshes
+    // don't change them under the user
+    generated_items: Vec<Suggestion>,
+
+    // set by background jobs whenever they have new results,
+    // so the suggestions for the last input get recalculated
+    background_updated: Arc<AtomicBool>,
+    file_hashes: FileHashCache,
+    path_index: PathIndex,
+    shell_history: ShellHistory,
+    file_index: FileIndex,
+    content_search: ContentSearch,
+    recent_files: RecentFiles,
+    clipboard_history: ClipboardHistory,
 }
 
 impl SuggestionMgr {
     pub fn new() -> Self {
+        let config = Config::load();
         let sysinfo_loader = SysInfoLoader::new();
         let session_mgr = Rc::new(SessionMgr::new());
         let static_items = SuggestionMgr::load_static_items(
@@ -90,19 +603,61 @@ impl SuggestionMgr {
             session_mgr.clone(),
         );
         let relevant_items = static_items.clone();
+        let background_updated = Arc::new(AtomicBool::new(false));
+        let file_hashes = FileHashCache::new(background_updated.clone());
+        let path_index = PathIndex::new(background_updated.clone());
+        let shell_history = ShellHistory::load(&config.history_files, background_updated.clone());
+        let content_search = ContentSearch::new(background_updated.clone());
+        let recent_files = RecentFiles::new(background_updated.clone());
+        let clipboard_history = ClipboardHistory::new(background_updated.clone());
+        let file_index = FileIndex::new(
+            &config.file_roots,
+            &config.file_excludes,
+            background_updated.clone(),
+        );
 
         Self {
+            config,
             sysinfo_loader,
             static_items,
             relevant_items,
             session_mgr,
+            quicklinks: QuicklinkStore::load(),
+            snippets: SnippetStore::load(),
+            charmap: CharMap::load(),
+            window_mgr: WindowMgr::new(background_updated.clone()),
+            process_mgr: ProcessMgr::new(),
+            service_mgr: ServiceMgr::new(background_updated.clone()),
+            last_input: String::new(),
+            generated_items: vec![],
+            background_updated,
+            file_hashes,
+            path_index,
+            shell_history,
+            file_index,
+            content_search,
+            recent_files,
+            clipboard_history,
         }
     }
 
     pub fn update(&mut self, input: &str) {
+        self.last_input = input.to_string();
+        self.generated_items = self.get_generator_suggestions(input);
         self.relevant_items = self.get_relevant_items(input);
     }
 
+    /// Recalculates the suggestions for the last input if a background job produced
+    /// new results since the last check, returns whether anything changed
+    pub fn refresh(&mut self) -> bool {
+        if !self.background_updated.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+
+        self.relevant_items = self.get_relevant_items(&self.last_input);
+        true
+    }
+
     pub fn get_suggestions(&self) -> &Vec<Suggestion> {
         &self.relevant_items
     }
@@ -113,161 +668,1404 @@ impl SuggestionMgr {
                 return Some(&it);
             }
         }
-
-        None
+
+        None
+    }
+
+    /// Runs the action at `action_index` in the suggestion's actions,
+    /// falling back to the first one when there's no action there
+    pub fn run(&self, suggestion: &Suggestion, action_index: usize) -> PostRunAction {
+        let action = match suggestion
+            .actions
+            .get(action_index)
+            .or(suggestion.actions.first())
+        {
+            Some(it) => &it.action,
+            None => return PostRunAction::Nothing,
+        };
+
+        match action {
+            // rows that only show something, running them shouldn't lose the input
+            Action::NoOp => return PostRunAction::Nothing,
+            Action::Open(app_type, target) => {
+                sysaction::try_run(&self.sysinfo_loader.get_open_cmd(app_type, target));
+            }
+            Action::OpenDefault(path) => match self.sysinfo_loader.get_file_open_cmd(path) {
+                Some(cmd) => {
+                    sysaction::try_run(&cmd);
+                }
+                None => println!("unable to open {}, there's no default application for it", path),
+            },
+            Action::OpenAtLine(path, line) => {
+                if let Some(cmd) = self.sysinfo_loader.get_open_at_line_cmd(path, *line) {
+                    sysaction::try_run(&cmd);
+                }
+            }
+            Action::Command(cmd) => {
+                sysaction::try_run(cmd);
+            }
+            Action::CaptureOutput(cmd) => {
+                return match sysaction::run_captured(cmd) {
+                    Ok(process) => PostRunAction::ShowOutput(process),
+                    Err(e) => {
+                        println!("unable to spawn process {}", e);
+                        PostRunAction::Nothing
+                    }
+                };
+            }
+            Action::Session(op) => self.session_mgr.perform(&op),
+            Action::CopyToClipboard(str) => sysaction::set_clipboard(str),
+            Action::CopySecret(str) => sysaction::set_clipboard_data(ClipboardData::secret(str)),
+            Action::Quicklink(op) => self.quicklinks.perform(op),
+            Action::Snippet(op) => self.snippets.perform(op),
+            Action::Reveal(path) => {
+                if let Err(e) = sysaction::reveal(path) {
+                    println!("unable to reveal {} in the file manager: {}", path, e);
+                    // at least open the folder it is in
+                    if let Some(parent) = Path::new(path).parent() {
+                        sysaction::try_run(&self.sysinfo_loader.get_open_cmd(
+                            &DefaultApplicationType::FileExplorer,
+                            &parent.to_string_lossy(),
+                        ));
+                    }
+                }
+            }
+            Action::CopyFileContents(path) => match fs::read(path) {
+                Ok(content) => {
+                    let mime_type = match SysInfoLoader::try_get_file_mime_type_str(path) {
+                        Some(DefaultApplicationType::Mime(mime_type)) => mime_type,
+                        _ => "application/octet-stream".to_owned(),
+                    };
+                    // source code, HTML and such also go as plain text, for apps that only take that
+                    let data = if mime_type.starts_with("text/") && mime_type != "text/plain" {
+                        ClipboardData::new(&mime_type, content.clone()).with("text/plain", content)
+                    } else {
+                        ClipboardData::new(&mime_type, content)
+                    };
+                    sysaction::set_clipboard_data(data);
+                }
+                Err(e) => println!("unable to read {}: {}", path, e),
+            },
+            Action::CopyHtml(html, text) => sysaction::set_clipboard_data(ClipboardData::html(html, text)),
+            Action::CopyFiles(paths) => {
+                let uris: Vec<String> = paths.iter().map(|it| urlutil::file_url(it)).collect();
+                sysaction::set_clipboard_data(ClipboardData::files(paths, &uris));
+            }
+            Action::Trash(path) => {
+                if let Err(e) = trashutil::trash(Path::new(path)) {
+                    println!("unable to move {} to the trash: {}", path, e);
+                }
+            }
+            Action::Rename(path, new_name) => {
+                let target = Path::new(path).with_file_name(new_name);
+                if target.exists() {
+                    println!("not renaming {}, {} already exists", path, target.to_string_lossy());
+                } else if let Err(e) = fs::rename(path, &target) {
+                    println!("unable to rename {}: {}", path, e);
+                }
+            }
+            Action::OpenTerminal(dir) => {
+                // the terminal prefix expects a command after it, so the shell is passed explicitly
+                if let Some(terminal) = &self.sysinfo_loader.terminal {
+                    let mut cmd = terminal.clone();
+                    cmd.push(shellutil::user_shell());
+                    sysaction::try_run_in(&cmd, Path::new(dir));
+                }
+            }
+            Action::SetInput(input) => return PostRunAction::SetInput(input.clone()),
+            Action::CopyClip(entry) => match (entry.kind, fs::read(entry.path())) {
+                // copied again with every format file managers look for
+                (ClipKind::Files, Ok(_)) => {
+                    let paths = entry.files();
+                    let uris: Vec<String> = paths.iter().map(|it| urlutil::file_url(it)).collect();
+                    sysaction::set_clipboard_data(ClipboardData::files(&paths, &uris));
+                }
+                (ClipKind::Text, Ok(content)) => sysaction::set_clipboard_data(ClipboardData::text(
+                    &String::from_utf8_lossy(&content),
+                )),
+                (ClipKind::Image, Ok(content)) => {
+                    sysaction::set_clipboard_data(ClipboardData::new(&entry.mime_type, content))
+                }
+                (_, Err(e)) => println!("unable to read clipboard entry {}: {}", entry.hash, e),
+            },
+            Action::FocusWindow(window) => self.window_mgr.focus(window),
+            Action::Signal(pid, signal) => self.process_mgr.signal(*pid, *signal),
+            Action::Unit(unit, op) => self.service_mgr.perform(unit, *op),
+            Action::CopyCharacter(text, character) => {
+                sysaction::set_clipboard(text);
+                self.charmap.record_recent(character);
+            }
+        };
+
+        PostRunAction::Close
+    }
+
+    pub fn run_by_id(&self, id: &str, action_index: usize) -> PostRunAction {
+        dbg!("run_by_id {}", id);
+        // background refreshes can drop the suggestion between showing and running it
+        match self.try_get_suggestion_by_id(id) {
+            Some(s) => self.run(s, action_index),
+            None => {
+                println!("not running {}, the suggestion is gone", id);
+                PostRunAction::Nothing
+            }
+        }
+    }
+
+    fn load_static_items(
+        locales: &Vec<String>,
+        desktop_entries: &Vec<DesktopEntry>,
+        session_mgr: Rc<SessionMgr>,
+    ) -> Vec<Suggestion> {
+        let mut items: Vec<Suggestion> = desktop_entries
+            .iter()
+            .filter(|e| !e.no_display())
+            .map(|e| Suggestion::from(e, &locales))
+            .collect();
+
+        if session_mgr.enable_suspend {
+            items.push(Suggestion {
+                id: "system.action.suspend".to_owned(),
+                title: "Suspend".to_owned(),
+                description: "Suspend the computer".to_owned(),
+                icon_path: None,
+                actions: vec![Action::Session(SessionOperation::Suspend).into()],
+                completion: None,
+            });
+        }
+
+        if session_mgr.enable_reboot {
+            items.push(Suggestion {
+                id: "system.action.restart".to_owned(),
+                title: "Restart".to_owned(),
+                description: "Restart the computer".to_owned(),
+                icon_path: None,
+                actions: vec![Action::Session(SessionOperation::Reboot).into()],
+                completion: None,
+            });
+        }
+
+        if session_mgr.enable_poweroff {
+            items.push(Suggestion {
+                id: "system.action.poweroff".to_owned(),
+                title: "Shutdown".to_owned(),
+                description: "Poweeer off the system".to_owned(),
+                icon_path: None,
+                actions: vec![Action::Session(SessionOperation::PoweOff).into()],
+                completion: None,
+            });
+        }
+
+        items
+    }
+
+    fn load_dynamic_items(&self, input: &str) -> Vec<Suggestion> {
+        let mut s: Vec<Suggestion> = self.get_rename_suggestions(input);
+        s.append(&mut self.get_trash_suggestions(input));
+
+        if let Some((engine, query)) =
+            searchengine::match_keyword(&self.config.search_engines, input)
+        {
+            s.push(Suggestion {
+                id: format!("action.search.keyword.{}", engine.keyword),
+                title: format!("Search {}: '{}'", engine.name, query),
+                description: engine.url(query),
+                icon_path: None,
+                actions: get_search_actions(engine.url(query), query),
+                completion: None,
+            });
+        }
+
+        let mut clipboard_suggestions = self.get_clipboard_history_suggestions(input);
+        s.append(&mut clipboard_suggestions);
+
+        let mut recent_suggestions = self.get_recent_suggestions(input);
+        s.append(&mut recent_suggestions);
+
+        let mut file_search_suggestions = self.get_file_search_suggestions(input);
+        s.append(&mut file_search_suggestions);
+
+        let mut content_search_suggestions = self.get_content_search_suggestions(input);
+        s.append(&mut content_search_suggestions);
+
+        let mut quicklink_suggestions = self.get_quicklink_suggestions(input);
+        s.append(&mut quicklink_suggestions);
+
+        let mut snippet_suggestions = self.get_snippet_suggestions(input);
+        s.append(&mut snippet_suggestions);
+
+        let mut character_suggestions = self.get_character_suggestions(input);
+        s.append(&mut character_suggestions);
+
+        let mut window_suggestions = self.get_window_suggestions(input);
+        s.append(&mut window_suggestions);
+
+        let mut process_suggestions = self.get_process_suggestions(input);
+        s.append(&mut process_suggestions);
+
+        let mut unit_suggestions = self.get_unit_suggestions(input);
+        s.append(&mut unit_suggestions);
+
+        let mut status_suggestions = get_system_status_suggestions(input);
+        s.append(&mut status_suggestions);
+
+        let mut folder_suggestions = self.get_folder_suggestions(input);
+        s.append(&mut folder_suggestions);
+
+        let mut math_suggestions = self.get_math_suggestions(input);
+        s.append(&mut math_suggestions);
+
+        let mut time_suggestions = self.get_time_suggestions(input);
+        s.append(&mut time_suggestions);
+
+        let mut codec_suggestions = self.get_codec_suggestions(input);
+        s.append(&mut codec_suggestions);
+
+        let mut generator_suggestions = self.generated_items.clone();
+        s.append(&mut generator_suggestions);
+
+        let mut hash_suggestions = self.get_hash_suggestions(input);
+        s.append(&mut hash_suggestions);
+
+        let mut unit_conversin_suggestions = self.get_unit_conversion_suggestions(input);
+        s.append(&mut unit_conversin_suggestions);
+
+        let mut history_suggestions = self.get_history_suggestions(input);
+        s.append(&mut history_suggestions);
+
+        // FIXME: find a way to focus the browser when this is done
+        let mut fallback_engines = vec![&self.config.default_search_engine];
+        fallback_engines.extend(self.config.extra_search_engines.iter());
+        let mut seen_engines = Vec::new();
+        for engine in fallback_engines
+            .into_iter()
+            .filter_map(|keyword| self.config.get_search_engine(keyword))
+        {
+            if seen_engines.contains(&engine.keyword) {
+                continue;
+            }
+            seen_engines.push(engine.keyword.clone());
+
+            s.push(Suggestion {
+                id: format!("action.search.{}", engine.keyword),
+                title: format!("Search {}: '{}'", engine.name, input),
+                description: engine.keyword.clone(),
+                icon_path: None,
+                actions: get_search_actions(engine.url(input), input),
+                completion: None,
+            });
+        }
+
+        s.append(&mut self.get_command_suggestions(input));
+
+        s
+    }
+
+    // the mode picked from the input syntax comes first as "Run command",
+    // the other ones are offered after it so the user can choose explicitly
+    fn get_command_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        if input.trim().is_empty() {
+            return vec![];
+        }
+
+        let default_mode = shellutil::default_run_mode(input);
+        let mut modes = vec![default_mode];
+        modes.extend(
+            [RunMode::Direct, RunMode::Shell, RunMode::Terminal]
+                .into_iter()
+                .filter(|mode| *mode != default_mode),
+        );
+
+        let mut s = self.get_executable_completions(input);
+        s.extend(modes
+            .into_iter()
+            .filter_map(|mode| {
+                let cmd = shellutil::cmd_for_mode(
+                    mode,
+                    input,
+                    self.sysinfo_loader.terminal.as_deref(),
+                )?;
+                let (id, title, description) = match mode {
+                    RunMode::Direct => ("direct", "Run directly", "Without a shell".to_owned()),
+                    RunMode::Shell => ("shell", "Run in shell", shellutil::user_shell()),
+                    RunMode::Terminal => ("terminal", "Run in terminal", cmd[0].clone()),
+                };
+                let title = if mode == default_mode {
+                    "Run command"
+                } else {
+                    title
+                };
+
+                let mut actions = vec![Action::Command(cmd).into()];
+                if mode == default_mode {
+                    actions.append(&mut self.get_command_actions(input));
+                }
+
+                Some(Suggestion {
+                    id: if mode == default_mode {
+                        "system.command".to_owned()
+                    } else {
+                        format!("system.command.{}", id)
+                    },
+                    title: format!("{}: '{}'", title, input),
+                    description,
+                    icon_path: None,
+                    actions,
+                    completion: None,
+                })
+            }));
+
+        if let Some(cmd) = shellutil::cmd_for_mode(default_mode, input, None) {
+            s.push(Suggestion {
+                id: "system.command.output".to_owned(),
+                title: format!("Run and show output: '{}'", input),
+                description: "Shows stdout and stderr below the input".to_owned(),
+                icon_path: None,
+                actions: vec![Action::CaptureOutput(cmd).into()],
+                completion: None,
+            });
+        }
+
+        s
+    }
+
+    // alternatives to running a command the usual way
+    fn get_command_actions(&self, input: &str) -> Vec<SuggestionAction> {
+        let mut actions = Vec::new();
+        if let Some(cmd) = shellutil::cmd_for_mode(shellutil::default_run_mode(input), input, None) {
+            actions.push(Action::CaptureOutput(cmd).into());
+        }
+        if let Some(cmd) = shellutil::cmd_for_mode(
+            RunMode::Terminal,
+            input,
+            self.sysinfo_loader.terminal.as_deref(),
+        ) {
+            actions.push(SuggestionAction::new("Run in terminal", Action::Command(cmd)));
+        }
+        actions.push(SuggestionAction::new(
+            "Copy command",
+            Action::CopyToClipboard(input.trim().to_string()),
+        ));
+        actions
+    }
+
+    fn get_history_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        if input.trim().chars().count() < MIN_HISTORY_INPUT_CHARS {
+            return vec![];
+        }
+
+        let mut s: Vec<Suggestion> = Vec::new();
+        for entry in self.shell_history.matching(input) {
+            let mode = shellutil::default_run_mode(&entry.command);
+            let description = if entry.count == 1 {
+                "From shell history".to_owned()
+            } else {
+                format!("From shell history, used {} times", entry.count)
+            };
+
+            if let Some(cmd) = shellutil::cmd_for_mode(mode, &entry.command, None) {
+                let mut actions = vec![Action::Command(cmd).into()];
+                actions.append(&mut self.get_command_actions(&entry.command));
+                s.push(Suggestion {
+                    id: format!("system.history {}", entry.command),
+                    title: entry.command.clone(),
+                    description,
+                    icon_path: None,
+                    actions,
+                    completion: Some(entry.command.clone()),
+                });
+            }
+        }
+
+        s
+    }
+
+    // executables on $PATH while the command name is typed, after it the
+    // last argument gets completed as a path
+    fn get_executable_completions(&self, input: &str) -> Vec<Suggestion> {
+        let input = input.trim_start();
+        let (head, last) = match input.rsplit_once(char::is_whitespace) {
+            Some(parts) => parts,
+            None => {
+                return self
+                    .path_index
+                    .matching(input)
+                    .into_iter()
+                    .map(|(name, path)| Suggestion {
+                        id: format!("system.command.executable {}", name),
+                        title: format!("Run '{}'", name),
+                        description: path.to_string_lossy().to_string(),
+                        icon_path: None,
+                        actions: vec![Action::Command(vec![path.to_string_lossy().to_string()]).into()],
+                        completion: Some(format!("{} ", name)),
+                    })
+                    .collect();
+            }
+        };
+
+        let is_path = last.starts_with(['/', '~', '.']) || last.contains('/');
+        if !is_path {
+            return vec![];
+        }
+
+        self.get_prefixed_path_completions("system.command.complete", head.trim_end(), last)
+    }
+
+    fn get_url_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let has_scheme_handler = |scheme: &str| self.sysinfo_loader.has_scheme_handler(scheme);
+        let detected = match urlutil::detect(input, has_scheme_handler) {
+            Some(detected) => detected,
+            None => return vec![],
+        };
+
+        let (app_type, target) = if detected.scheme == "file" {
+            let path = match urlutil::file_url_path(&detected.url) {
+                Some(path) => path,
+                None => return vec![],
+            };
+            let app_type = if Path::new(&path).is_dir() {
+                Some(DefaultApplicationType::FileExplorer)
+            } else {
+                SysInfoLoader::try_get_file_mime_type_str(&path)
+            };
+            match app_type {
+                Some(app_type) => (app_type, path),
+                None => return vec![],
+            }
+        } else if has_scheme_handler(&detected.scheme) {
+            (
+                DefaultApplicationType::scheme_handler(&detected.scheme),
+                detected.url.clone(),
+            )
+        } else if detected.scheme == "http" || detected.scheme == "https" {
+            (DefaultApplicationType::Browser, detected.url.clone())
+        } else {
+            return vec![];
+        };
+
+        vec![Suggestion {
+            id: format!("system.url.open {}", detected.url),
+            title: format!("Open {}", detected.url),
+            description: String::new(),
+            icon_path: None,
+            actions: vec![
+                Action::Open(app_type, target).into(),
+                SuggestionAction::new("Copy URL", Action::CopyToClipboard(detected.url.clone())),
+                SuggestionAction::new(
+                    "Copy as link",
+                    Action::CopyHtml(
+                        format!(
+                            "<a href=\"{}\">{}</a>",
+                            html_escape::encode_double_quoted_attribute(&detected.url),
+                            html_escape::encode_text(&detected.url),
+                        ),
+                        detected.url.clone(),
+                    ),
+                ),
+            ],
+            completion: None,
+        }]
+    }
+
+    // "<name> <args>" runs a quicklink, "ql add <name> <template>" and
+    // "ql rm <name>" manage them
+    fn get_quicklink_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (name, args) = input.trim().split_once(' ').unwrap_or((input.trim(), ""));
+        if name.is_empty() {
+            return vec![];
+        }
+
+        if name == QUICKLINK_COMMAND {
+            return self.get_quicklink_management_suggestions(args);
+        }
+
+        if let Some(link) = self.quicklinks.get(name) {
+            let (title, actions, completion) = match link.fill(args) {
+                FilledQuicklink::Url(url) => (
+                    format!("Open {}: '{}'", link.name, url),
+                    vec![
+                        Action::Open(get_url_app_type(&url), url.clone()).into(),
+                        SuggestionAction::new("Copy URL", Action::CopyToClipboard(url)),
+                    ],
+                    None,
+                ),
+                FilledQuicklink::Command(cmd) => (
+                    format!("Run {}: '{}'", link.name, cmd.join(" ")),
+                    vec![
+                        Action::Command(cmd.clone()).into(),
+                        Action::CaptureOutput(cmd.clone()).into(),
+                        SuggestionAction::new("Copy command", Action::CopyToClipboard(cmd.join(" "))),
+                    ],
+                    None,
+                ),
+                FilledQuicklink::Missing(placeholders) => (
+                    format!("{}: type {}", link.name, placeholders.join(", ")),
+                    vec![Action::NoOp.into()],
+                    Some(format!("{} ", link.name)),
+                ),
+            };
+
+            return vec![Suggestion {
+                id: format!("quicklink.run {}", link.name),
+                title,
+                description: link.template().to_string(),
+                icon_path: None,
+                actions,
+                completion,
+            }];
+        }
+
+        // while the name is being typed offer the quicklinks it could be
+        if !args.is_empty() {
+            return vec![];
+        }
+
+        self.quicklinks
+            .links()
+            .into_iter()
+            .filter(|link| link.name.to_lowercase().starts_with(&name.to_lowercase()))
+            .map(|link| Suggestion {
+                id: format!("quicklink.complete {}", link.name),
+                title: format!("Quicklink: {}", link.name),
+                description: link.template().to_string(),
+                icon_path: None,
+                actions: vec![Action::NoOp.into()],
+                completion: Some(format!("{} ", link.name)),
+            })
+            .collect()
+    }
+
+    fn get_quicklink_management_suggestions(&self, args: &str) -> Vec<Suggestion> {
+        let (operation, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+        match operation {
+            "add" => {
+                let (name, template) = match rest.trim().split_once(' ') {
+                    Some((name, template)) if !template.trim().is_empty() => {
+                        (name, template.trim())
+                    }
+                    _ => return vec![],
+                };
+                let link = Quicklink::parse(name, template, |scheme| {
+                    self.sysinfo_loader.has_scheme_handler(scheme)
+                });
+                let replaces = self.quicklinks.get(name).is_some();
+
+                vec![Suggestion {
+                    id: format!("quicklink.add {}", name),
+                    title: format!(
+                        "{} quicklink '{}': '{}'",
+                        if replaces { "Replace" } else { "Create" },
+                        name,
+                        template
+                    ),
+                    description: format!("Placeholders: {}", link.placeholders().join(", ")),
+                    icon_path: None,
+                    actions: vec![Action::Quicklink(QuicklinkOperation::Add(link)).into()],
+                    completion: None,
+                }]
+            }
+            "rm" | "delete" => self
+                .quicklinks
+                .links()
+                .into_iter()
+                .filter(|link| link.name.to_lowercase().starts_with(&rest.trim().to_lowercase()))
+                .map(|link| Suggestion {
+                    id: format!("quicklink.remove {}", link.name),
+                    title: format!("Delete quicklink '{}'", link.name),
+                    description: link.template().to_string(),
+                    icon_path: None,
+                    actions: vec![Action::Quicklink(QuicklinkOperation::Remove(link.name.clone())).into()],
+                    completion: Some(format!("{} rm {}", QUICKLINK_COMMAND, link.name)),
+                })
+                .collect(),
+            _ => self
+                .quicklinks
+                .links()
+                .into_iter()
+                .map(|link| Suggestion {
+                    id: format!("quicklink.complete {}", link.name),
+                    title: format!("Quicklink: {}", link.name),
+                    description: link.template().to_string(),
+                    icon_path: None,
+                    actions: vec![Action::NoOp.into()],
+                    completion: Some(format!("{} ", link.name)),
+                })
+                .collect(),
+        }
+    }
+
+    // "snip <name> <fields>" fills a snippet, "snip add <name>" saves what's in
+    // the clipboard as one and "snip rm <name>" deletes it
+    fn get_snippet_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let args = match input.strip_prefix(SNIPPET_COMMAND) {
+            Some(args) if args.is_empty() || args.starts_with(' ') => args.trim_start(),
+            _ => return vec![],
+        };
+        let (first, rest) = args.split_once(' ').unwrap_or((args, ""));
+
+        match first {
+            "add" => {
+                let name = rest.trim();
+                if name.is_empty() || name.contains('/') {
+                    return vec![];
+                }
+                let replaces = self.snippets.get(name).is_some();
+
+                vec![Suggestion {
+                    id: format!("snippet.add {}", name),
+                    title: format!(
+                        "{} snippet '{}' with the clipboard text",
+                        if replaces { "Replace" } else { "Create" },
+                        name
+                    ),
+                    description: "Placeholders: {date}, {time}, {clipboard}, {cursor} or any {field}"
+                        .to_owned(),
+                    icon_path: None,
+                    actions: vec![Action::Snippet(SnippetOperation::AddFromClipboard(name.to_string())).into()],
+                    completion: None,
+                }]
+            }
+            "rm" | "delete" => self
+                .snippets
+                .matching("")
+                .into_iter()
+                .filter(|it| it.name.to_lowercase().starts_with(&rest.trim().to_lowercase()))
+                .map(|it| Suggestion {
+                    id: format!("snippet.remove {}", it.name),
+                    title: format!("Delete snippet '{}'", it.name),
+                    description: it.preview(),
+                    icon_path: None,
+                    actions: vec![Action::Snippet(SnippetOperation::Remove(it.name.clone())).into()],
+                    completion: Some(format!("{} rm {}", SNIPPET_COMMAND, it.name)),
+                })
+                .collect(),
+            _ => {
+                // an exact name goes straight to filling it
+                if let Some(it) = self.snippets.get(first) {
+                    let (title, actions, completion) = match it.fill(rest) {
+                        FilledSnippet::Filled(text) => (
+                            format!("Snippet {}: '{}'", it.name, text.preview()),
+                            self.get_snippet_actions(text),
+                            None,
+                        ),
+                        // the fields are still being typed, enter keeps the launcher open
+                        FilledSnippet::Missing(fields) => (
+                            format!("{}: type {}", it.name, fields.join(", ")),
+                            vec![Action::NoOp.into()],
+                            Some(format!("{} {} ", SNIPPET_COMMAND, it.name)),
+                        ),
+                    };
+
+                    return vec![Suggestion {
+                        id: format!("snippet.use {}", it.name),
+                        title,
+                        description: it.preview(),
+                        icon_path: None,
+                        actions,
+                        completion,
+                    }];
+                }
+
+                self.snippets
+                    .matching(args)
+                    .into_iter()
+                    .map(|it| {
+                        // ones with fields have to be completed first, enter starts that
+                        let completion = format!("{} {} ", SNIPPET_COMMAND, it.name);
+                        let actions = match it.fill("") {
+                            FilledSnippet::Filled(text) => self.get_snippet_actions(text),
+                            FilledSnippet::Missing(_) => vec![Action::SetInput(completion.clone()).into()],
+                        };
+                        Suggestion {
+                            id: format!("snippet.complete {}", it.name),
+                            title: format!("Snippet: {}", it.name),
+                            description: it.preview(),
+                            icon_path: None,
+                            actions,
+                            completion: Some(completion),
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // typing it is only offered when there's a tool to do it
+    fn get_snippet_actions(&self, text: SnippetText) -> Vec<SuggestionAction> {
+        let mut actions = vec![Action::Snippet(SnippetOperation::Copy(text.clone())).into()];
+        if self.snippets.can_type() {
+            actions.push(Action::Snippet(SnippetOperation::Type(text)).into());
+        }
+        actions
+    }
+
+    // "unit <name>", user units go first since those are the ones usually restarted
+    fn get_unit_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match UNIT_COMMANDS.iter().find_map(|command| {
+            input
+                .strip_prefix(command)
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        }) {
+            Some(query) => query.trim().to_lowercase(),
+            None => return vec![],
+        };
+
+        self.service_mgr
+            .units()
+            .into_iter()
+            .filter(|it| {
+                it.name.to_lowercase().contains(&query)
+                    || it.description.to_lowercase().contains(&query)
+            })
+            .take(UNIT_LIMIT)
+            .map(|it| {
+                let mut actions: Vec<SuggestionAction> = if it.is_active() {
+                    vec![
+                        Action::Unit(it.clone(), UnitOperation::Restart).into(),
+                        Action::Unit(it.clone(), UnitOperation::Stop).into(),
+                    ]
+                } else {
+                    vec![Action::Unit(it.clone(), UnitOperation::Start).into()]
+                };
+                match it.file_state.as_deref() {
+                    Some("enabled") => actions.push(Action::Unit(it.clone(), UnitOperation::Disable).into()),
+                    Some("disabled") => actions.push(Action::Unit(it.clone(), UnitOperation::Enable).into()),
+                    _ => (),
+                }
+                if let Some(cmd) = shellutil::cmd_for_mode(
+                    RunMode::Terminal,
+                    &it.journal_cmd(),
+                    self.sysinfo_loader.terminal.as_deref(),
+                ) {
+                    actions.push(SuggestionAction::new("Show journal", Action::Command(cmd)));
+                }
+
+                let bus = match it.bus {
+                    UnitBus::User => "user",
+                    UnitBus::System => "system",
+                };
+                let mut state = vec![format!("{} ({})", it.active_state, it.sub_state)];
+                state.extend(it.file_state.clone());
+                if !it.description.is_empty() {
+                    state.insert(0, it.description.clone());
+                }
+
+                Suggestion {
+                    id: format!("system.unit.{} {}", bus, it.name),
+                    title: format!("{} ({})", it.name, bus),
+                    description: state.join(" · "),
+                    icon_path: None,
+                    actions,
+                    completion: None,
+                }
+            })
+            .collect()
+    }
+
+    // "ps <name or pid>", "kill port 8080", and "kill <pid> <signal>" to confirm
+    // signals to processes of other users
+    fn get_process_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match PROCESS_COMMANDS.iter().find_map(|command| {
+            input
+                .strip_prefix(command)
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        }) {
+            Some(query) => query.trim(),
+            None => return vec![],
+        };
+
+        if let Some(port) = query.strip_prefix(PROCESS_PORT_PREFIX) {
+            return self.get_port_suggestions(port.trim());
+        }
+
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if let [pid, signal] = words[..]
+            && let (Ok(pid), Some(signal)) = (pid.parse::<u32>(), Signal::parse(signal))
+        {
+            return match self.process_mgr.process(pid) {
+                Some(process) => vec![Suggestion {
+                    id: format!("system.process.confirm {} {}", pid, signal.name()),
+                    title: format!(
+                        "Send {} to {} ({}) of {}",
+                        signal.name(),
+                        process.name,
+                        pid,
+                        process.user
+                    ),
+                    description: if self.process_mgr.is_own(&process) {
+                        process.cmdline
+                    } else {
+                        "Needs administrator rights".to_owned()
+                    },
+                    icon_path: None,
+                    actions: vec![SuggestionAction::new(
+                        &format!("Send {}", signal.name()),
+                        Action::Signal(pid, signal),
+                    )],
+                    completion: None,
+                }],
+                None => vec![],
+            };
+        }
+
+        let query = query.to_lowercase();
+        let mut processes: Vec<Process> = self
+            .process_mgr
+            .processes()
+            .into_iter()
+            .filter(|it| {
+                it.name.to_lowercase().contains(&query)
+                    || it.cmdline.to_lowercase().contains(&query)
+                    || it.pid.to_string().starts_with(&query)
+            })
+            .collect();
+        processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+
+        processes
+            .into_iter()
+            .take(PROCESS_LIMIT)
+            .map(|it| self.get_process_suggestion(it, ""))
+            .collect()
+    }
+
+    fn get_port_suggestions(&self, port: &str) -> Vec<Suggestion> {
+        let port = match port.parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => return vec![],
+        };
+
+        let owners = self.process_mgr.port_owners(port);
+        if owners.is_empty() {
+            return vec![Suggestion {
+                id: format!("system.process.port {}", port),
+                title: format!("Nothing is listening on port {}", port),
+                description: String::new(),
+                icon_path: None,
+                actions: vec![Action::NoOp.into()],
+                completion: None,
+            }];
+        }
+
+        owners
+            .into_iter()
+            .map(|owner| match owner {
+                PortOwner::Process(process) => {
+                    self.get_process_suggestion(process, &format!("Port {}: ", port))
+                }
+                PortOwner::Hidden => Suggestion {
+                    id: format!("system.process.port {}", port),
+                    title: format!("Port {} is used by a process of another user", port),
+                    description: "Its process can't be seen without administrator rights".to_owned(),
+                    icon_path: None,
+                    actions: vec![Action::NoOp.into()],
+                    completion: None,
+                },
+            })
+            .collect()
+    }
+
+    // processes of other users only get to the confirmation, signaling them asks for a password
+    fn get_process_suggestion(&self, process: Process, title_prefix: &str) -> Suggestion {
+        let own = self.process_mgr.is_own(&process);
+        let signal_action = |label: &str, signal: Signal| {
+            if own {
+                SuggestionAction::new(label, Action::Signal(process.pid, signal))
+            } else {
+                SuggestionAction::new(
+                    &format!("{}…", label),
+                    Action::SetInput(format!(
+                        "{} {} {}",
+                        PROCESS_COMMANDS[1],
+                        process.pid,
+                        signal.name().trim_start_matches("SIG").to_lowercase()
+                    )),
+                )
+            }
+        };
+
+        let mut actions = vec![
+            signal_action("Terminate", Signal::Term),
+            signal_action("Force kill", Signal::Kill),
+            if process.stopped {
+                signal_action("Resume", Signal::Cont)
+            } else {
+                signal_action("Pause", Signal::Stop)
+            },
+            SuggestionAction::new("Copy PID", Action::CopyToClipboard(process.pid.to_string())),
+        ];
+        if let Some(dir) = process.exe.as_ref().and_then(|exe| exe.parent()) {
+            actions.push(SuggestionAction::new(
+                "Open executable folder",
+                Action::Open(
+                    DefaultApplicationType::FileExplorer,
+                    dir.to_string_lossy().to_string(),
+                ),
+            ));
+        }
+
+        Suggestion {
+            id: format!("system.process {}", process.pid),
+            title: format!(
+                "{}{} ({}){}",
+                title_prefix,
+                process.name,
+                process.pid,
+                if process.stopped { ", paused" } else { "" }
+            ),
+            description: format!(
+                "{} · {:.1}% CPU · {} · {}",
+                process.user,
+                process.cpu,
+                describe_size(process.rss),
+                process.cmdline
+            ),
+            icon_path: None,
+            actions,
+            completion: None,
+        }
+    }
+
+    // "win <title or app>", the app icon comes from its desktop entry when there's one
+    fn get_window_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match input.strip_prefix(WINDOW_COMMAND) {
+            Some(query) if query.is_empty() || query.starts_with(' ') => query.trim().to_lowercase(),
+            _ => return vec![],
+        };
+
+        self.window_mgr
+            .windows()
+            .into_iter()
+            .filter(|it| {
+                it.title.to_lowercase().contains(&query) || it.app_id.to_lowercase().contains(&query)
+            })
+            .map(|it| {
+                let entry = self
+                    .sysinfo_loader
+                    .desktop_entries
+                    .iter()
+                    .find(|e| it.belongs_to(&get_app_names(e)));
+                let app_name = entry
+                    .and_then(|e| e.name(&self.sysinfo_loader.locales))
+                    .map(|name| name.to_string())
+                    .unwrap_or(it.app_id.clone());
+
+                Suggestion {
+                    id: format!("system.window {:?}", it.id),
+                    title: it.title.clone(),
+                    description: app_name,
+                    icon_path: entry.and_then(|e| e.icon()).map(|icon| icon.to_string()),
+                    actions: vec![Action::FocusWindow(it).into()],
+                    completion: None,
+                }
+            })
+            .collect()
+    }
+
+    // launching an app that's already open gives it a second window,
+    // so switching to the open one becomes the default
+    fn with_running_window(&self, mut item: Suggestion) -> Suggestion {
+        let entry = match self
+            .sysinfo_loader
+            .desktop_entries
+            .iter()
+            .find(|e| e.id() == item.id)
+        {
+            Some(entry) => entry,
+            None => return item,
+        };
+
+        if let Some(window) = self.window_mgr.find_app_window(&get_app_names(entry)) {
+            item.actions.insert(
+                0,
+                SuggestionAction::new("Switch to running window", Action::FocusWindow(window)),
+            );
+        }
+        item
+    }
+
+    // "emoji <name>", "char <name>" or just a codepoint like "U+2192"
+    fn get_character_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let command_query = |command: &str| match input.strip_prefix(command) {
+            Some(query) if query.is_empty() || query.starts_with(' ') => Some(query),
+            _ => None,
+        };
+
+        if let Some(query) = command_query(EMOJI_COMMAND) {
+            return self
+                .charmap
+                .matching_emoji(query, CHARACTER_LIMIT)
+                .into_iter()
+                .map(|it| {
+                    let mut actions = get_character_actions(&it.emoji);
+                    // after the copy as ones, so those keep their shortcuts
+                    for (variant, tone) in it.skin_tone_variants() {
+                        actions.push(SuggestionAction::new(
+                            &format!("Copy {} {}", variant, tone),
+                            Action::CopyCharacter(variant, it.emoji.clone()),
+                        ));
+                    }
+
+                    Suggestion {
+                        id: format!("charmap.emoji {}", it.emoji),
+                        title: format!("{}  {}", it.emoji, it.name),
+                        description: it.keywords.join(", "),
+                        icon_path: None,
+                        actions,
+                        completion: None,
+                    }
+                })
+                .collect();
+        }
+
+        let query = match command_query(CHARACTER_COMMAND) {
+            Some(query) => query,
+            None if input.starts_with("U+") || input.starts_with("u+") => input,
+            None => return vec![],
+        };
+
+        self.charmap
+            .matching_characters(query, CHARACTER_LIMIT)
+            .into_iter()
+            .map(|it| {
+                let character = it.character.to_string();
+                Suggestion {
+                    id: format!("charmap.character {}", character),
+                    title: format!("{}  {}", character, it.name),
+                    description: charmap::codepoints(&character),
+                    icon_path: None,
+                    actions: get_character_actions(&character),
+                    completion: None,
+                }
+            })
+            .collect()
+    }
+
+    // FIXME: adding math resolution as a normal suggestion listItem foor now
+    //        there should be a better UI for it
+    fn get_math_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        // dates like "2026-01-05 - 2025-11-30" are also valid arithmetic,
+        // so they need to be handled before evalexpr gets to them
+        if let Some(result) = dateutil::evaluate(input) {
+            return get_date_suggestions(result);
+        }
+
+        match evalexpr::eval(input) {
+            Ok(result) => vec![Suggestion {
+                id: "evaluation.calc".to_owned(),
+                title: format!("Result: '{}'", result),
+                description: String::new(),
+                icon_path: None,
+                actions: vec![
+                    SuggestionAction::new("Copy result", Action::CopyToClipboard(result.to_string())),
+                    SuggestionAction::new(
+                        "Copy expression",
+                        Action::CopyToClipboard(format!("{} = {}", input.trim(), result)),
+                    ),
+                ],
+                completion: None,
+            }],
+            Err(_) => vec![],
+        }
     }
 
-    pub fn run(&self, suggestion: &Suggestion) -> PostRunAction {
-        match &suggestion.action {
-            Action::NoOp => (),
-            Action::Open(app_type, target) => {
-                sysaction::try_run(&self.sysinfo_loader.get_open_cmd(app_type, &target))
+    fn get_time_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (t, mut zones) = match timeutil::parse_query(input) {
+            Some(TimeQuery::Epoch(t)) => {
+                return vec![
+                    ("seconds", t.timestamp().to_string()),
+                    ("milliseconds", t.timestamp_millis().to_string()),
+                    (
+                        "nanoseconds",
+                        t.timestamp_nanos_opt().unwrap_or_default().to_string(),
+                    ),
+                ]
+                .into_iter()
+                .map(|(unit, value)| Suggestion {
+                    id: format!("evaluation.time.epoch.{}", unit),
+                    title: format!("Epoch: {}", value),
+                    description: format!("Unix time in {}", unit),
+                    icon_path: None,
+                    actions: vec![Action::CopyToClipboard(value).into()],
+                    completion: None,
+                })
+                .collect();
             }
-            Action::Command(cmd) => sysaction::try_run(&cmd),
-            Action::Session(op) => self.session_mgr.perform(&op),
-            Action::CopyToClipboard(str) => set_clipboard(&str),
+            Some(TimeQuery::Instant(t, zones)) => (t, zones),
+            None => return vec![],
         };
 
-        PostRunAction::Close
-    }
-
-    pub fn run_by_id(&self, id: &str) -> PostRunAction {
-        dbg!("run_by_id {}", id);
-        let s = self.try_get_suggestion_by_id(id).expect(&format!(
-            "Expected referenced suggestionId to always be valid, id = {}",
-            id
-        ));
-
-        self.run(s)
-    }
-
-    fn load_static_items(
-        locales: &Vec<String>,
-        desktop_entries: &Vec<DesktopEntry>,
-        session_mgr: Rc<SessionMgr>,
-    ) -> Vec<Suggestion> {
-        let mut items: Vec<Suggestion> = desktop_entries
-            .iter()
-            .filter(|e| !e.no_display())
-            .map(|e| Suggestion::from(e, &locales))
-            .collect();
+        zones.push(Zone::local());
+        zones.push(Zone::utc());
+        zones.extend(self.config.time_zones.iter().filter_map(|it| Zone::resolve(it)));
 
-        if session_mgr.enable_suspend {
-            items.push(Suggestion {
-                id: "system.action.suspend".to_owned(),
-                title: "Suspend".to_owned(),
-                description: "Suspend the computer".to_owned(),
-                icon_path: None,
-                action: Action::Session(SessionOperation::Suspend),
-                completion: None,
-            });
-        }
+        let mut s = Vec::new();
+        let mut seen_labels = Vec::new();
+        for zone in zones {
+            if seen_labels.contains(&zone.label) {
+                continue;
+            }
+            seen_labels.push(zone.label.clone());
 
-        if session_mgr.enable_reboot {
-            items.push(Suggestion {
-                id: "system.action.restart".to_owned(),
-                title: "Restart".to_owned(),
-                description: "Restart the computer".to_owned(),
+            let zoned = zone.at(&t);
+            s.push(Suggestion {
+                id: format!("evaluation.time.zone {}", zoned.label),
+                title: format!("{}: {}", zoned.label, zoned.display()),
+                description: zoned.offset_display(),
                 icon_path: None,
-                action: Action::Session(SessionOperation::Reboot),
+                actions: vec![Action::CopyToClipboard(zoned.time.to_rfc3339()).into()],
                 completion: None,
             });
         }
 
-        if session_mgr.enable_poweroff {
-            items.push(Suggestion {
-                id: "system.action.poweroff".to_owned(),
-                title: "Shutdown".to_owned(),
-                description: "Poweeer off the system".to_owned(),
+        let formats = [
+            ("unix", "Unix timestamp", t.timestamp().to_string()),
+            ("unix-ms", "Unix timestamp (ms)", t.timestamp_millis().to_string()),
+            ("iso8601", "ISO 8601", t.to_rfc3339()),
+            ("rfc2822", "RFC 2822", t.to_rfc2822()),
+        ];
+        for (id, name, value) in formats {
+            s.push(Suggestion {
+                id: format!("evaluation.time.{}", id),
+                title: format!("{}: {}", name, value),
+                description: String::new(),
                 icon_path: None,
-                action: Action::Session(SessionOperation::PoweOff),
+                actions: vec![Action::CopyToClipboard(value).into()],
                 completion: None,
             });
         }
 
-        items
+        s
     }
 
-    fn load_dynamic_items(&self, input: &str) -> Vec<Suggestion> {
-        let mut s: Vec<Suggestion> = Vec::new();
+    // FIXME: adding codec results as a normal suggestion listItem foor now
+    //        there should be a better UI for it
+    fn get_codec_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        if let Some((codec, text)) = codecutil::parse_prefix(input) {
+            let mut s = Vec::new();
+            if codec == Codec::Jwt {
+                return get_jwt_suggestions(text);
+            }
 
-        // TODO: will need some refactoring to make this work correctly
-        // let mut finder_suggestions = self.get_finder_suggestions(input);
-        // s.append(&mut finder_suggestions);
+            if let Ok(encoded) = codecutil::encode(codec, text) {
+                s.push(Suggestion {
+                    id: format!("evaluation.codec.{}.encode", codec.id()),
+                    title: format!("{} encoded text: '{}'", codec.name(), encoded),
+                    description: String::new(),
+                    icon_path: None,
+                    actions: vec![Action::CopyToClipboard(encoded).into()],
+                    completion: None,
+                });
+            }
 
-        let mut folder_suggestions = self.get_folder_suggestions(input);
-        s.append(&mut folder_suggestions);
+            if let Ok(decoded) = codecutil::decode(codec, text) {
+                s.push(Suggestion {
+                    id: format!("evaluation.codec.{}.decode", codec.id()),
+                    title: format!("{} decoded text: '{}'", codec.name(), decoded),
+                    description: String::new(),
+                    icon_path: None,
+                    actions: vec![Action::CopyToClipboard(decoded).into()],
+                    completion: None,
+                });
+            }
 
-        let mut math_suggestions = self.get_math_suggestions(input);
-        s.append(&mut math_suggestions);
+            return s;
+        }
 
-        let mut b64_suggestions = self.get_b64_conversion_suggestions(input);
-        s.append(&mut b64_suggestions);
+        codecutil::detect(input)
+            .into_iter()
+            .flat_map(|codec| match codec {
+                Codec::Jwt => get_jwt_suggestions(input),
+                _ => match codecutil::decode(codec, input.trim()) {
+                    Ok(decoded) => vec![Suggestion {
+                        id: format!("evaluation.codec.{}.decode", codec.id()),
+                        title: format!("{} decoded text: '{}'", codec.name(), decoded),
+                        description: String::new(),
+                        icon_path: None,
+                        actions: vec![Action::CopyToClipboard(decoded).into()],
+                        completion: None,
+                    }],
+                    Err(_) => vec![],
+                },
+            })
+            .collect()
+    }
 
-        let mut unit_conversin_suggestions = self.get_unit_conversion_suggestions(input);
-        s.append(&mut unit_conversin_suggestions);
+    // values are generated again on every update, so each query gets fresh ones
+    fn get_generator_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (command, args) = input.trim().split_once(' ').unwrap_or((input.trim(), ""));
+        let generated: Vec<(&str, String, String)> = match command.to_lowercase().as_str() {
+            "uuid" => vec![
+                ("uuid.v4", genutil::uuid_v4(), "UUID v4".to_owned()),
+                ("uuid.v7", genutil::uuid_v7(), "UUID v7".to_owned()),
+            ],
+            "uuid4" => vec![("uuid.v4", genutil::uuid_v4(), "UUID v4".to_owned())],
+            "uuid7" => vec![("uuid.v7", genutil::uuid_v7(), "UUID v7".to_owned())],
+            "ulid" => vec![("ulid", genutil::ulid(), "ULID".to_owned())],
+            "password" | "pwgen" => match PasswordOptions::parse(args) {
+                Some(opts) => vec![("password", genutil::password(&opts), opts.describe())],
+                None => vec![],
+            },
+            "random" => {
+                let range = if args.is_empty() {
+                    Some((1, 100))
+                } else {
+                    genutil::parse_range(args)
+                };
+                match range {
+                    Some((min, max)) => vec![(
+                        "random",
+                        genutil::random_in_range(min, max).to_string(),
+                        format!("Random number between {} and {}", min, max),
+                    )],
+                    None => vec![],
+                }
+            }
+            "dice" | "roll" => match genutil::roll_dice(args) {
+                Some(roll) => {
+                    let rolls: Vec<String> = roll.rolls.iter().map(|r| r.to_string()).collect();
+                    let modifier = match roll.modifier {
+                        0 => String::new(),
+                        m => format!(" {:+}", m),
+                    };
+                    vec![(
+                        "dice",
+                        roll.total.to_string(),
+                        format!("{}: [{}]{}", args.trim(), rolls.join(", "), modifier),
+                    )]
+                }
+                None => vec![],
+            },
+            "lorem" => match genutil::parse_lorem_args(args) {
+                Some((count, unit)) => vec![(
+                    "lorem",
+                    genutil::lorem(count, unit),
+                    format!("Lorem ipsum, {} {:?}", count, unit).to_lowercase(),
+                )],
+                None => vec![],
+            },
+            _ => vec![],
+        };
 
-        // FIXME: find a way to focus the browser when this is done
-        s.push(Suggestion {
-            id: "action.search".to_owned(),
-            title: format!("Search: '{}'", input),
-            // TODO: see what should i add here
-            description: String::new(),
-            icon_path: None,
-            // FIXME: there's no way to correctly separate an argument string, event if the user
-            //        uses simple/double quotes or just puts the string with spaces in there
-            action: Action::Open(DefaultApplicationType::Browser, get_brave_search_url(input)),
-            completion: None,
-        });
+        generated
+            .into_iter()
+            .map(|(id, value, description)| Suggestion {
+                id: format!("generator.{}", id),
+                // lorem ipsum can be several paragraphs long, the whole text is still copied
+                title: match value.char_indices().nth(GENERATED_TITLE_MAX_CHARS) {
+                    Some((idx, _)) => format!("{}…", &value[..idx]),
+                    None => value.clone(),
+                },
+                description,
+                icon_path: None,
+                // one-off values, passwords above all, are kept out of the clipboard history
+                actions: vec![Action::CopySecret(value).into()],
+                completion: None,
+            })
+            .collect()
+    }
 
-        s.push(Suggestion {
-            id: "system.command".to_owned(),
-            title: format!("Run command: '{}'", input),
-            // TODO: see what should i add here
-            description: String::new(),
-            icon_path: None,
-            // FIXME: there's no way to correctly separate an argument string, event if the user
-            //        uses simple/double quotes or just puts the string with spaces in there
-            action: Action::Command(input.split(" ").map(|s| s.to_string()).collect()),
-            completion: None,
-        });
+    // "sha256 <text or file>", "hash <text or file>" or "verify <checksum> <file>"
+    fn get_hash_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (prefix, rest) = match input.split_once(' ') {
+            Some(parts) => parts,
+            None => return vec![],
+        };
+
+        if prefix == "verify" {
+            return self.get_hash_verify_suggestions(rest);
+        }
+
+        let preferred = Algorithm::from_prefix(prefix);
+        if preferred.is_none() && prefix != "hash" {
+            return vec![];
+        }
+
+        let target_path = fsutil::expand_home(rest);
+        let mut s = if Path::new(&target_path).is_file() {
+            match self.file_hashes.get_or_start(Path::new(&target_path)) {
+                FileHashState::Done(digests) => get_digest_suggestions(digests, preferred),
+                state => vec![get_file_hash_state_suggestion(rest, &state)],
+            }
+        } else {
+            get_digest_suggestions(hashutil::hash_bytes(rest.as_bytes()), preferred)
+        };
 
+        s.append(&mut self.get_prefixed_path_completions("evaluation.hash.complete", prefix, rest));
         s
     }
 
-    // FIXME: adding math resolution as a normal suggestion listItem foor now
-    //        there should be a better UI for it
-    fn get_math_suggestions(&self, input: &str) -> Vec<Suggestion> {
-        match evalexpr::eval(input) {
-            Ok(result) => vec![Suggestion {
-                id: "evaluation.calc".to_owned(),
-                title: format!("Result: '{}'", result),
-                description: String::new(),
-                icon_path: None,
-                action: Action::CopyToClipboard(result.to_string()),
-                completion: None,
-            }],
-            Err(_) => vec![],
+    fn get_hash_verify_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (expected, path_input) = match input.split_once(' ') {
+            Some((expected, path_input)) => (expected.to_lowercase(), path_input),
+            None => return vec![],
+        };
+
+        let candidates = Algorithm::candidates_for_digest(&expected);
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        let target_path = fsutil::expand_home(path_input);
+        let mut s = Vec::new();
+        if Path::new(&target_path).is_file() {
+            match self.file_hashes.get_or_start(Path::new(&target_path)) {
+                FileHashState::Done(digests) => {
+                    let matching = digests
+                        .iter()
+                        .find(|d| candidates.contains(&d.algorithm) && d.hex == expected);
+                    let title = match matching {
+                        Some(d) => format!("Checksum matches ({})", d.algorithm.name()),
+                        None => "Checksum does NOT match".to_owned(),
+                    };
+                    let description = digests
+                        .iter()
+                        .filter(|d| candidates.contains(&d.algorithm))
+                        .map(|d| format!("{}: {}", d.algorithm.name(), d.hex))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    s.push(Suggestion {
+                        id: "evaluation.hash.verify".to_owned(),
+                        title,
+                        description,
+                        icon_path: None,
+                        actions: vec![Action::NoOp.into()],
+                        completion: None,
+                    });
+                }
+                state => s.push(get_file_hash_state_suggestion(path_input, &state)),
+            }
         }
+
+        s.append(&mut self.get_prefixed_path_completions(
+            "evaluation.hash.complete",
+            &format!("verify {}", expected),
+            path_input,
+        ));
+        s
     }
 
-    // FIXME: adding math resolution as a normal suggestion listItem foor now
-    //        there should be a better UI for it
-    fn get_b64_conversion_suggestions(&self, input: &str) -> Vec<Suggestion> {
-        match BASE64_STANDARD.decode(input) {
-            Ok(result) => match String::from_utf8(result) {
-                Ok(str) => vec![Suggestion {
-                    id: "evaluation.b64".to_owned(),
-                    title: format!("Base64 converted text: '{}'", str),
+    // reuses the folder listing so file arguments can be tab-completed
+    fn get_prefixed_path_completions(
+        &self,
+        id_prefix: &str,
+        prefix: &str,
+        path_input: &str,
+    ) -> Vec<Suggestion> {
+        self.get_folder_suggestions(path_input)
+            .into_iter()
+            .filter_map(|it| {
+                let completion = it.completion?;
+                Some(Suggestion {
+                    id: format!("{} {}", id_prefix, completion),
+                    title: format!("{}: '{}'", prefix, completion),
                     description: String::new(),
                     icon_path: None,
-                    action: Action::CopyToClipboard(str),
-                    completion: None,
-                }],
-                Err(_) => vec![],
-            },
-            Err(_) => vec![],
-        }
+                    actions: vec![Action::NoOp.into()],
+                    completion: Some(format!("{} {}", prefix, completion)),
+                })
+            })
+            .collect()
     }
 
     fn get_unit_conversion_suggestions(&self, input: &str) -> Vec<Suggestion> {
@@ -301,7 +2099,7 @@ impl SuggestionMgr {
                         ),
                         description: String::new(),
                         icon_path: None,
-                        action: Action::NoOp,
+                        actions: vec![Action::NoOp.into()],
                         completion: None,
                     }],
                     Err(_) => vec![],
@@ -315,13 +2113,7 @@ impl SuggestionMgr {
     // TODO: tab-complete selected folder suggestion
     fn get_folder_suggestions(&self, input: &str) -> Vec<Suggestion> {
         let mut s: Vec<Suggestion> = Vec::new();
-        let home_path = env::var("HOME").expect("expected $HOME to always be defined");
-        let starts_with_home_path_subst = input.chars().nth(0).map_or(false, |c| c == '~');
-        let final_input_path = if starts_with_home_path_subst {
-            input.replace("~", &home_path)
-        } else {
-            input.to_string()
-        };
+        let final_input_path = fsutil::expand_home(input);
 
         let path = Path::new(&final_input_path);
         if path.is_dir() {
@@ -335,9 +2127,9 @@ impl SuggestionMgr {
                 icon_path: None,
                 // FIXME: there's no way to correctly separate an argument string, event if the user
                 //        uses simple/double quotes or just puts the string with spaces in there
-                action: Action::Open(
+                actions: self.get_open_file_actions(
                     DefaultApplicationType::FileExplorer,
-                    final_input_path.to_string(),
+                    &final_input_path,
                 ),
                 completion: None,
             });
@@ -353,7 +2145,7 @@ impl SuggestionMgr {
                     icon_path: None,
                     // fixme: there's no way to correctly separate an argument string, event if the user
                     //        uses simple/double quotes or just puts the string with spaces in there
-                    action: Action::Open(app_type, final_input_path.to_string()),
+                    actions: self.get_open_file_actions(app_type, &final_input_path),
                     completion: None,
                 });
             }
@@ -373,7 +2165,9 @@ impl SuggestionMgr {
                         let path_str = path.to_string_lossy();
                         let path_uppercase_str = path_str.to_uppercase();
                         let mut completion = path_str.to_string();
-                        completion.push_str("/");
+                        if path.is_dir() {
+                            completion.push_str("/");
+                        }
                         if path_uppercase_str.contains(&final_input_path.to_uppercase())
                             && !path_uppercase_str.eq(&final_input_path.to_uppercase())
                         {
@@ -390,9 +2184,9 @@ impl SuggestionMgr {
                                     icon_path: None,
                                     // FIXME: there's no way to correctly separate an argument string, event if the user
                                     //        uses simple/double quotes or just puts the string with spaces in there
-                                    action: Action::Open(
+                                    actions: self.get_open_file_actions(
                                         DefaultApplicationType::FileExplorer,
-                                        path.to_string_lossy().into(),
+                                        &path_str,
                                     ),
                                     completion: Some(completion),
                                 });
@@ -413,7 +2207,7 @@ impl SuggestionMgr {
                                         icon_path: None,
                                         // FIXME: there's no way to correctly separate an argument string, event if the user
                                         //        uses simple/double quotes or just puts the string with spaces in there
-                                        action: Action::Open(app_type, path_str.into_owned()),
+                                        actions: self.get_open_file_actions(app_type, &path_str),
                                         completion: Some(completion),
                                     });
                                 }
@@ -427,52 +2221,359 @@ impl SuggestionMgr {
         s
     }
 
-    fn get_finder_suggestions(&self, input: &str) -> Vec<Suggestion> {
-        if !input.starts_with("find ") {
-            return vec![];
+    fn get_file_search_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match input.strip_prefix(FILE_SEARCH_COMMAND) {
+            Some(query) if query.starts_with(' ') && !query.trim().is_empty() => query.trim(),
+            _ => return vec![],
+        };
+
+        self.file_index
+            .search(query)
+            .into_iter()
+            .map(|path| {
+                let path_str = path.to_string_lossy().to_string();
+                let name = path
+                    .file_name()
+                    .map(|it| it.to_string_lossy().to_string())
+                    .unwrap_or(path_str.clone());
+
+                Suggestion {
+                    id: format!("system.file.search {}", path_str),
+                    title: name,
+                    description: path_str.clone(),
+                    icon_path: None,
+                    actions: self.get_open_path_actions(&path_str),
+                    completion: None,
+                }
+            })
+            .collect()
+    }
+
+    // everything else that can be done with a file or folder besides opening it,
+    // offered as the alternative actions of the suggestions showing it
+    fn get_file_actions(&self, path: &str) -> Vec<SuggestionAction> {
+        let path_buf = Path::new(path);
+        let name = match path_buf.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return vec![],
+        };
+        // symlinks are kept, so the link is what gets trashed or copied and not its target
+        let absolute_path = std::path::absolute(fsutil::expand_home(path))
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or(path.to_string());
+        let folder = if path_buf.is_dir() {
+            Some(absolute_path.clone())
+        } else {
+            Path::new(&absolute_path)
+                .parent()
+                .map(|it| it.to_string_lossy().to_string())
+        };
+
+        let mut actions = vec![
+            Action::Reveal(absolute_path.clone()).into(),
+            Action::CopyFiles(vec![absolute_path.clone()]).into(),
+            SuggestionAction::new("Copy path", Action::CopyToClipboard(absolute_path.clone())),
+            SuggestionAction::new(
+                "Copy URI",
+                Action::CopyToClipboard(urlutil::file_url(&absolute_path)),
+            ),
+        ];
+        if path_buf.is_file() {
+            actions.push(Action::CopyFileContents(absolute_path.clone()).into());
+        }
+        actions.push(SuggestionAction::new(
+            "Rename…",
+            Action::SetInput(format!("{} {}{}{}", RENAME_COMMAND, path, RENAME_SEPARATOR, name)),
+        ));
+        // trashing goes through a confirmation, a typed "~" is a path like any other
+        if !is_protected_path(&absolute_path) {
+            actions.push(SuggestionAction::new(
+                "Move to trash…",
+                Action::SetInput(format!("{} {}", TRASH_COMMAND, absolute_path)),
+            ));
+        }
+        if let (Some(folder), Some(_)) = (folder, &self.sysinfo_loader.terminal) {
+            actions.push(Action::OpenTerminal(folder).into());
+        }
+
+        actions
+    }
+
+    // opening it, followed by the other file actions
+    fn get_open_file_actions(&self, app_type: DefaultApplicationType, path: &str) -> Vec<SuggestionAction> {
+        let mut actions = vec![Action::Open(app_type, path.to_string()).into()];
+        actions.append(&mut self.get_file_actions(path));
+        actions
+    }
+
+    // like get_open_file_actions, without finding out the MIME type of files up front
+    fn get_open_path_actions(&self, path: &str) -> Vec<SuggestionAction> {
+        if Path::new(path).is_dir() {
+            return self.get_open_file_actions(DefaultApplicationType::FileExplorer, path);
         }
 
-        let parts: Vec<&str> = input["find ".len()..].split(" in ").collect();
-        let pattern = parts.get(0)
-            .expect("should always have the first item, considering string starts with 'find '")
-            .trim();
-        let input_location = parts.get(1);
+        let mut actions = vec![Action::OpenDefault(path.to_string()).into()];
+        actions.append(&mut self.get_file_actions(path));
+        actions
+    }
+
+    // "rename <path> as <new name>", filled in by the "Rename…" file action
+    fn get_rename_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let rest = match input
+            .strip_prefix(RENAME_COMMAND)
+            .filter(|rest| rest.starts_with(' '))
+        {
+            Some(rest) => rest.trim_start(),
+            None => return vec![],
+        };
 
-        if pattern.len() == 0 {
+        // both the path and the new name may have the separator in them,
+        // it's the first split that leaves an existing path before it
+        let (path, new_name) = match rest.match_indices(RENAME_SEPARATOR).find_map(|(i, _)| {
+            let path = fsutil::expand_home(&rest[..i]);
+            Path::new(&path)
+                .exists()
+                .then(|| (path, rest[i + RENAME_SEPARATOR.len()..].trim()))
+        }) {
+            Some(parts) => parts,
+            None => return vec![],
+        };
+        let name = match Path::new(&path).file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return vec![],
+        };
+        if new_name.is_empty() || new_name.contains('/') || new_name == name {
             return vec![];
         }
 
-        let home_path = env::var("HOME").expect("expected system to always have a home directory");
+        let (title, action) = if Path::new(&path).with_file_name(new_name).exists() {
+            (format!("Can't rename, '{}' already exists", new_name), Action::NoOp)
+        } else {
+            (
+                format!("Rename '{}' to '{}'", name, new_name),
+                Action::Rename(path.clone(), new_name.to_string()),
+            )
+        };
+
+        vec![Suggestion {
+            id: format!("system.file.rename.confirm {}", path),
+            title,
+            description: path,
+            icon_path: None,
+            actions: vec![action.into()],
+            completion: None,
+        }]
+    }
+
+    // "trash <path>", filled in by the "Move to trash…" file action
+    fn get_trash_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let path = match input
+            .strip_prefix(TRASH_COMMAND)
+            .filter(|rest| rest.starts_with(' ') && !rest.trim().is_empty())
+        {
+            Some(rest) => fsutil::expand_home(rest.trim()),
+            None => return vec![],
+        };
+        // a symlink is trashed itself, trashutil only resolves the folder it's in
+        if fs::symlink_metadata(&path).is_err() {
+            return vec![];
+        }
+        let absolute_path = match std::path::absolute(&path) {
+            Ok(it) => it.to_string_lossy().to_string(),
+            Err(_) => return vec![],
+        };
+        let name = Path::new(&absolute_path)
+            .file_name()
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or(absolute_path.clone());
 
-        let location = if let Some(location) = input_location {
-            location.to_owned()
+        let (title, action) = if is_protected_path(&absolute_path) {
+            (format!("Can't move '{}' to the trash", name), Action::NoOp)
         } else {
-            &home_path.to_string()
+            (
+                format!("Move '{}' to the trash", name),
+                Action::Trash(absolute_path.clone()),
+            )
+        };
+
+        vec![Suggestion {
+            id: format!("system.file.trash.confirm {}", absolute_path),
+            title,
+            description: absolute_path,
+            icon_path: None,
+            actions: vec![action.into()],
+            completion: None,
+        }]
+    }
+
+    fn get_recent_file_suggestion(&self, file: &RecentFile) -> Suggestion {
+        // the recorded type saves looking it up, but it may have no app to open it anymore
+        let mut actions = match &file.mime_type {
+            Some(mime) if self.sysinfo_loader.has_default_app(mime) => vec![
+                Action::Open(DefaultApplicationType::Mime(mime.clone()), file.path.clone()).into(),
+            ],
+            Some(_) => vec![],
+            None => vec![Action::OpenDefault(file.path.clone()).into()],
+        };
+        actions.append(&mut self.get_file_actions(&file.path));
+        let description = match &file.application {
+            Some(application) => format!("{} · {}", file.path, application),
+            None => file.path.clone(),
+        };
+
+        Suggestion {
+            id: format!("system.file.recent {}", file.path),
+            title: file.name(),
+            description,
+            icon_path: None,
+            actions,
+            completion: None,
+        }
+    }
+
+    // "recent" lists the latest files, "recent <query>" filters them by name
+    fn get_recent_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match input.strip_prefix(RECENT_FILES_COMMAND) {
+            Some(query) if query.is_empty() || query.starts_with(' ') => query,
+            _ => return vec![],
         };
-        if !Path::new(location).is_dir() {
-            println!("location {} is no a directory, ignoring", location);
+
+        self.recent_files
+            .matching(query, RECENT_FILES_LIMIT)
+            .iter()
+            .map(|file| self.get_recent_file_suggestion(file))
+            .collect()
+    }
+
+    fn get_boosted_recent_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        if input.trim().chars().count() < MIN_BOOSTED_RECENT_INPUT_CHARS
+            || input.starts_with(RECENT_FILES_COMMAND)
+        {
             return vec![];
         }
 
-        let result = find(location, pattern);
-        result
-            .split("\n")
-            .filter_map(|item| {
-                match SysInfoLoader::try_get_file_mime_type_str(item) {
-                    Some(app_type) => Some(Suggestion {
-                        id: format!("system.file.open {}", item),
-                        title: format!("open file: '{}'", item),
-                        description: String::new(),
-                        icon_path: None,
-                        action: Action::Open(app_type, item.to_string()),
-                        completion: Some(item.to_string()),
-                    }),
-                    None => None,
+        self.recent_files
+            .matching(input, BOOSTED_RECENT_FILES_LIMIT)
+            .iter()
+            .map(|file| self.get_recent_file_suggestion(file))
+            .collect()
+    }
+
+    // "clip" lists what was copied recently, "clip <query>" searches it
+    fn get_clipboard_history_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let query = match input.strip_prefix(CLIPBOARD_HISTORY_COMMAND) {
+            Some(query) if query.is_empty() || query.starts_with(' ') => query,
+            _ => return vec![],
+        };
+
+        self.clipboard_history
+            .matching(query, CLIPBOARD_HISTORY_LIMIT)
+            .into_iter()
+            .map(|entry| {
+                let copied_at = entry.time.with_timezone(&Local).format("%b %d, %H:%M");
+                let (description, icon_path) = match entry.kind {
+                    ClipKind::Text if entry.primary => (format!("Selected text · {}", copied_at), None),
+                    ClipKind::Text => (format!("Text · {}", copied_at), None),
+                    // the row shows files as images when the path exists
+                    ClipKind::Image => (
+                        format!("{} · {}", describe_size(entry.size), copied_at),
+                        Some(entry.path().to_string_lossy().to_string()),
+                    ),
+                    ClipKind::Files => (
+                        format!("{} files · {}", entry.files().len(), copied_at),
+                        None,
+                    ),
+                };
+
+                let mut actions = vec![Action::CopyClip(entry.clone()).into()];
+                match entry.kind {
+                    ClipKind::Files => actions.push(SuggestionAction::new(
+                        "Copy paths",
+                        Action::CopyToClipboard(entry.files().join("\n")),
+                    )),
+                    ClipKind::Image if self.sysinfo_loader.has_default_app(&entry.mime_type) => {
+                        actions.push(SuggestionAction::new(
+                            "Open image",
+                            Action::Open(
+                                DefaultApplicationType::Mime(entry.mime_type.clone()),
+                                entry.path().to_string_lossy().to_string(),
+                            ),
+                        ))
+                    }
+                    ClipKind::Image => (),
+                    ClipKind::Text => (),
+                }
+
+                Suggestion {
+                    id: format!("system.clipboard {}", entry.hash),
+                    title: entry.preview(),
+                    description,
+                    icon_path,
+                    actions,
+                    completion: None,
                 }
             })
             .collect()
     }
 
+    // "in:~/notes kubernetes ingress", matches keep coming while the search runs
+    fn get_content_search_suggestions(&self, input: &str) -> Vec<Suggestion> {
+        let (dir_input, pattern) = match input
+            .strip_prefix(CONTENT_SEARCH_PREFIX)
+            .and_then(|rest| rest.split_once(' '))
+        {
+            Some((dir_input, pattern)) if !pattern.trim().is_empty() => (dir_input, pattern.trim()),
+            _ => return vec![],
+        };
+
+        let root = fsutil::expand_home(dir_input);
+        let root = Path::new(&root);
+        if !root.is_dir() {
+            return vec![];
+        }
+
+        let state = self.content_search.get_or_start(root, pattern);
+        let mut s: Vec<Suggestion> = state
+            .matches
+            .iter()
+            .map(|m| {
+                let relative_path = m.path.strip_prefix(root).unwrap_or(&m.path);
+                let path_str = m.path.to_string_lossy().to_string();
+                let mut actions = vec![Action::OpenAtLine(path_str.clone(), m.line).into()];
+                actions.append(&mut self.get_open_path_actions(&path_str));
+
+                Suggestion {
+                    id: format!("system.file.content {}:{}", path_str, m.line),
+                    title: format!("{}:{}", relative_path.to_string_lossy(), m.line),
+                    description: m.snippet.clone(),
+                    icon_path: None,
+                    actions,
+                    completion: None,
+                }
+            })
+            .collect();
+
+        let status = if !state.done {
+            Some(format!("Searching for '{}' in '{}'…", pattern, dir_input))
+        } else if s.is_empty() {
+            Some(format!("No files in '{}' contain '{}'", dir_input, pattern))
+        } else {
+            None
+        };
+        if let Some(title) = status {
+            s.push(Suggestion {
+                id: "system.file.content.status".to_owned(),
+                title,
+                description: String::new(),
+                icon_path: None,
+                actions: vec![Action::NoOp.into()],
+                completion: None,
+            });
+        }
+
+        s
+    }
+
     fn filter_relevant_static_items(&self, input: &str) -> Vec<Suggestion> {
         self.static_items
             .iter()
@@ -481,12 +2582,15 @@ impl SuggestionMgr {
                     .to_uppercase()
                     .contains(input.to_uppercase().as_str())
             })
-            .map(|it| it.clone())
+            .map(|it| self.with_running_window(it.clone()))
             .collect()
     }
 
     fn get_relevant_items(&self, input: &str) -> Vec<Suggestion> {
-        let mut relevant_items = self.filter_relevant_static_items(input);
+        // an URL is almost certainly what the user wants to open, so it goes first
+        let mut relevant_items = self.get_url_suggestions(input);
+        relevant_items.append(&mut self.get_boosted_recent_suggestions(input));
+        relevant_items.append(&mut self.filter_relevant_static_items(input));
         relevant_items.append(&mut self.load_dynamic_items(input));
         relevant_items
     }
@@ -509,7 +2613,17 @@ impl Suggestion {
             title: name.clone(),
             description,
             icon_path: e.icon().map(|s| s.to_string()),
-            action: Action::from(&e),
+            actions: vec![
+                SuggestionAction::new("Launch", Action::from(e)),
+                SuggestionAction::new(
+                    "Copy command",
+                    Action::CopyToClipboard(SysInfoLoader::cmd(e).join(" ")),
+                ),
+                SuggestionAction::new(
+                    "Reveal desktop file",
+                    Action::Reveal(e.path.to_string_lossy().to_string()),
+                ),
+            ],
             completion: None,
         }
     }
@@ -521,4 +2635,47 @@ impl Action {
     fn from(e: &DesktopEntry) -> Self {
         Self::Command(SysInfoLoader::cmd(e))
     }
+
+    // shown in the actions panel when the provider doesn't give a more specific one
+    fn label(&self) -> String {
+        match self {
+            Action::NoOp => "No action",
+            Action::Open(DefaultApplicationType::FileExplorer, _) => "Open folder",
+            Action::Open(DefaultApplicationType::Browser, _) => "Open in browser",
+            Action::Open(DefaultApplicationType::Mime(_), _) => "Open",
+            Action::OpenDefault(_) => "Open",
+            Action::OpenAtLine(_, _) => "Open at line",
+            Action::Command(_) => "Run",
+            Action::CaptureOutput(_) => "Run and show output",
+            Action::Session(SessionOperation::Suspend) => "Suspend",
+            Action::Session(SessionOperation::Reboot) => "Restart",
+            Action::Session(SessionOperation::PoweOff) => "Shutdown",
+            Action::CopyToClipboard(_) => "Copy",
+            Action::CopySecret(_) => "Copy",
+            Action::Quicklink(QuicklinkOperation::Add(_)) => "Save quicklink",
+            Action::Quicklink(QuicklinkOperation::Remove(_)) => "Remove quicklink",
+            Action::Snippet(SnippetOperation::Copy(_)) => "Copy",
+            Action::Snippet(SnippetOperation::Type(_)) => "Type into window",
+            Action::Snippet(SnippetOperation::AddFromClipboard(_)) => "Save snippet",
+            Action::Snippet(SnippetOperation::Remove(_)) => "Delete snippet",
+            Action::Reveal(_) => "Reveal in file manager",
+            Action::CopyFileContents(_) => "Copy contents",
+            Action::CopyHtml(_, _) => "Copy as formatted text",
+            Action::CopyFiles(_) => "Copy file",
+            Action::Trash(_) => "Move to trash",
+            Action::Rename(_, _) => "Rename",
+            Action::OpenTerminal(_) => "Open terminal here",
+            Action::SetInput(_) => "Edit",
+            Action::CopyClip(_) => "Copy",
+            Action::CopyCharacter(_, _) => "Copy",
+            Action::FocusWindow(_) => "Switch to window",
+            Action::Signal(_, _) => "Send signal",
+            Action::Unit(_, UnitOperation::Start) => "Start",
+            Action::Unit(_, UnitOperation::Stop) => "Stop",
+            Action::Unit(_, UnitOperation::Restart) => "Restart",
+            Action::Unit(_, UnitOperation::Enable) => "Enable",
+            Action::Unit(_, UnitOperation::Disable) => "Disable",
+        }
+        .to_owned()
+    }
 }
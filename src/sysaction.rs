@@ -1,37 +1,285 @@
-use std::{io::Read, process::{Child, Command, Stdio}};
-
-pub fn try_run(cmd: &Vec<String>) {
-    if let Some(app) = cmd.get(0) {
-        let mut command = Command::new(app);
-        let args = &cmd[1..];
-        for it in args {
-            command.arg(&it);
+use std::{
+    env,
+    io::{self, Read},
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use zbus::blocking::Connection;
+
+use crate::{
+    clipboard::{self, ClipboardData},
+    fsutil, urlutil,
+};
+
+const OUTPUT_READ_BUFFER_SIZE: usize = 4096;
+// commands like `yes` or `journalctl -f` never stop writing, only the end is kept
+pub const MAX_CAPTURED_OUTPUT_SIZE: usize = 64 * 1024;
+// seconds to wait for the launcher to close, so the previous window has the focus again
+const TYPING_DELAY: &str = "0.3";
+// KEY_LEFT from linux/input-event-codes.h, ydotool only takes key codes
+const YDOTOOL_LEFT_KEY: &str = "105";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypingTool {
+    Wtype,
+    Ydotool,
+    Xdotool,
+}
+
+#[derive(Debug, Default)]
+struct CapturedOutput {
+    // the last MAX_CAPTURED_OUTPUT_SIZE bytes at most
+    text: String,
+    // bytes dropped from the start to stay under the limit
+    dropped: usize,
+}
+
+impl CapturedOutput {
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+        if self.text.len() <= MAX_CAPTURED_OUTPUT_SIZE {
+            return;
         }
 
-        let output = command.spawn();
-        match output {
-            Err(e) => println!("unable to spawn process {}", e.to_string()),
-            _ => (),
+        let mut cut = self.text.len() - MAX_CAPTURED_OUTPUT_SIZE;
+        while !self.text.is_char_boundary(cut) {
+            cut += 1;
         }
+        self.text.drain(..cut);
+        self.dropped += cut;
     }
 }
 
-pub fn find(root: &str, name: &str) -> String {
-    let mut command = Command::new("find");
-    let name_query = format!("*{}*", name);
-    command.args(vec![root, "-name", &name_query]);
-    dbg!(&command);
+/// Output received since a previous read
+#[derive(Debug)]
+pub struct OutputChunk {
+    pub text: String,
+    // false when part of what came after the previous read was dropped already,
+    // then the text is everything still kept and replaces what was read before
+    pub continues: bool,
+    // bytes received so far, where the next read starts
+    pub received: usize,
+}
+
+/// A process started with its stdout and stderr captured, both streams are
+/// collected into the same buffer in the order they arrive
+#[derive(Debug, Clone)]
+pub struct CapturedProcess {
+    child: Arc<Mutex<Child>>,
+    output: Arc<Mutex<CapturedOutput>>,
+    // streams that still haven't reached EOF
+    open_streams: Arc<AtomicUsize>,
+}
+
+impl CapturedProcess {
+    /// The output still kept, the last `MAX_CAPTURED_OUTPUT_SIZE` bytes of it
+    pub fn output(&self) -> String {
+        self.output.lock().expect("CapturedProcess output poisoned").text.clone()
+    }
+
+    /// Output that came after the first `received` bytes
+    pub fn output_after(&self, received: usize) -> OutputChunk {
+        let output = self.output.lock().expect("CapturedProcess output poisoned");
+        let total = output.dropped + output.text.len();
+        match received.checked_sub(output.dropped) {
+            Some(start) => OutputChunk {
+                text: output.text[start.min(output.text.len())..].to_string(),
+                continues: true,
+                received: total,
+            },
+            None => OutputChunk {
+                text: output.text.clone(),
+                continues: false,
+                received: total,
+            },
+        }
+    }
+
+    /// Whether the start of the output was dropped to stay under the size limit
+    pub fn is_truncated(&self) -> bool {
+        self.output.lock().expect("CapturedProcess output poisoned").dropped > 0
+    }
 
-    let child = command.stdout(Stdio::piped())
-        .spawn()
-        .expect("failed to execute child");
+    /// The exit status, if the process already finished
+    pub fn try_exit_status(&self) -> Option<ExitStatus> {
+        let mut child = self.child.lock().expect("CapturedProcess child poisoned");
+        child.try_wait().ok().flatten()
+    }
+
+    /// Whether the process exited and all of its output was read
+    pub fn is_finished(&self) -> bool {
+        self.try_exit_status().is_some() && self.open_streams.load(Ordering::Relaxed) == 0
+    }
 
-    let output = child
-        .wait_with_output()
-        .expect("failed to wait on child");
+    /// Kills the whole process group, whatever the command started would otherwise
+    /// keep the output open
+    pub fn kill(&self) {
+        let child = self.child.lock().expect("CapturedProcess child poisoned");
+        if unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } != 0 {
+            println!("unable to kill process {}", io::Error::last_os_error());
+        }
+    }
+}
 
-    let data = output.stdout.to_vec();
+fn build_command(cmd: &[String]) -> Option<Command> {
+    let app = cmd.first()?;
+    let mut command = Command::new(app);
+    command.args(&cmd[1..]);
+    Some(command)
+}
 
-    let out = String::from_utf8(data.to_vec()).expect("unable to interpret output as string");
-    out
+pub fn try_run(cmd: &Vec<String>) -> Option<Child> {
+    let mut command = build_command(cmd)?;
+    match command.spawn() {
+        Ok(child) => Some(child),
+        Err(e) => {
+            println!("unable to spawn process {}", e.to_string());
+            None
+        }
+    }
+}
+
+/// Runs the command with `dir` as its working directory
+pub fn try_run_in(cmd: &[String], dir: &Path) -> Option<Child> {
+    let mut command = build_command(cmd)?;
+    match command.current_dir(dir).spawn() {
+        Ok(child) => Some(child),
+        Err(e) => {
+            println!("unable to spawn process {}", e.to_string());
+            None
+        }
+    }
+}
+
+/// Asks the file manager to show the file selected in its folder,
+/// through the org.freedesktop.FileManager1 DBus interface
+pub fn reveal(path: &str) -> zbus::Result<()> {
+    let connection = Connection::session()?;
+    connection.call_method(
+        Some("org.freedesktop.FileManager1"),
+        "/org/freedesktop/FileManager1",
+        Some("org.freedesktop.FileManager1"),
+        "ShowItems",
+        &(vec![urlutil::file_url(path)], ""),
+    )?;
+
+    Ok(())
+}
+
+pub fn run_captured(cmd: &[String]) -> io::Result<CapturedProcess> {
+    let mut command = build_command(cmd)
+        .ok_or(io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    let output = Arc::new(Mutex::new(CapturedOutput::default()));
+    let open_streams = Arc::new(AtomicUsize::new(2));
+    let stdout = child.stdout.take().map(|it| Box::new(it) as Box<dyn Read + Send>);
+    let stderr = child.stderr.take().map(|it| Box::new(it) as Box<dyn Read + Send>);
+
+    for stream in [stdout, stderr] {
+        let output = output.clone();
+        let open_streams = open_streams.clone();
+        thread::spawn(move || {
+            if let Some(mut stream) = stream {
+                let mut buffer = [0; OUTPUT_READ_BUFFER_SIZE];
+                let mut pending: Vec<u8> = Vec::new();
+                while let Ok(n) = stream.read(&mut buffer) {
+                    if n == 0 {
+                        break;
+                    }
+
+                    pending.extend_from_slice(&buffer[..n]);
+                    output
+                        .lock()
+                        .expect("CapturedProcess output poisoned")
+                        .push(&take_decodable(&mut pending));
+                }
+                output
+                    .lock()
+                    .expect("CapturedProcess output poisoned")
+                    .push(&String::from_utf8_lossy(&pending));
+            }
+            open_streams.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+
+    Ok(CapturedProcess {
+        child: Arc::new(Mutex::new(child)),
+        output,
+        open_streams,
+    })
+}
+
+// a character split between two reads is kept in `pending` until the rest of it arrives
+fn take_decodable(pending: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => pending.len(),
+    };
+    let text = String::from_utf8_lossy(&pending[..complete]).to_string();
+    pending.drain(..complete);
+    text
+}
+
+pub fn set_clipboard_data(data: ClipboardData) {
+    if let Err(e) = clipboard::copy(data) {
+        println!("unable to copy to clipboard {}", e);
+    }
+}
+
+pub fn set_clipboard(value: &str) {
+    set_clipboard_data(ClipboardData::text(value));
+}
+
+/// The virtual keyboard tool that works in the current session, if one is installed
+pub fn find_typing_tool() -> Option<TypingTool> {
+    let is_wayland = env::var_os("WAYLAND_DISPLAY").is_some_and(|it| !it.is_empty());
+    let candidates = if is_wayland {
+        [(TypingTool::Wtype, "wtype"), (TypingTool::Ydotool, "ydotool")]
+    } else {
+        [(TypingTool::Xdotool, "xdotool"), (TypingTool::Ydotool, "ydotool")]
+    };
+
+    candidates
+        .into_iter()
+        .find(|(_, name)| fsutil::find_in_path(name).is_some())
+        .map(|(tool, _)| tool)
+}
+
+/// Types the text into the window that gets the focus after the launcher closes,
+/// then moves the cursor back `cursor_offset` characters
+pub fn type_text(tool: TypingTool, text: &str, cursor_offset: usize) {
+    let text = shell_words::quote(text);
+    let (type_cmd, move_cmd, move_arg) = match tool {
+        TypingTool::Wtype => (format!("wtype -- {}", text), "wtype", " -k Left".to_owned()),
+        TypingTool::Ydotool => (
+            format!("ydotool type -- {}", text),
+            "ydotool key",
+            format!(" {}:1 {}:0", YDOTOOL_LEFT_KEY, YDOTOOL_LEFT_KEY),
+        ),
+        TypingTool::Xdotool => (
+            format!("xdotool type --clearmodifiers -- {}", text),
+            "xdotool key",
+            " Left".to_owned(),
+        ),
+    };
+
+    // runs in a shell on its own, the launcher is gone by the time it types
+    let mut script = format!("sleep {} && {}", TYPING_DELAY, type_cmd);
+    if cursor_offset > 0 {
+        script.push_str(&format!(" && {}{}", move_cmd, move_arg.repeat(cursor_offset)));
+    }
+    try_run(&vec!["sh".to_owned(), "-c".to_owned(), script]);
 }
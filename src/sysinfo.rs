@@ -1,11 +1,66 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    env,
+    ffi::{CStr, CString},
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+    time::Duration,
+};
+
 use freedesktop_desktop_entry::{DesktopEntry, Iter, default_paths, get_languages_from_env};
 use xdg_utils::{query_default_app, query_mime_info};
 
+use crate::fsutil;
+
 const DIRECTORY_MIMETYPE: &str = "inode/directory";
 const BROWSER_MIMETYPE: &str = "text/html";
+const SCHEME_HANDLER_MIMETYPE_PREFIX: &str = "x-scheme-handler/";
 
 const DEFAULT_SEARCH_URL: &str = "";
 
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+const MEMINFO_FILE: &str = "/proc/meminfo";
+const MOUNTS_FILE: &str = "/proc/self/mounts";
+const UPTIME_FILE: &str = "/proc/uptime";
+const LOADAVG_FILE: &str = "/proc/loadavg";
+const IPV4_ROUTE_FILE: &str = "/proc/net/route";
+const IPV6_ROUTE_FILE: &str = "/proc/net/ipv6_route";
+// read-only images mounted by snap and flatpak, not something anyone wants to see here
+const HIDDEN_FILESYSTEMS: [&str; 2] = ["squashfs", "erofs"];
+
+// known terminal emulators, with the arguments that go before the command to run
+const TERMINALS: [(&str, &[&str]); 12] = [
+    ("x-terminal-emulator", &["-e"]),
+    ("kgx", &["--"]),
+    ("ptyxis", &["--"]),
+    ("gnome-terminal", &["--"]),
+    ("konsole", &["-e"]),
+    ("xfce4-terminal", &["-x"]),
+    ("ghostty", &["-e"]),
+    ("alacritty", &["-e"]),
+    ("kitty", &[]),
+    ("foot", &[]),
+    ("wezterm", &["start", "--"]),
+    ("xterm", &["-e"]),
+];
+
+// editors that can be told which line to open a file at, with their arguments
+const LINE_AWARE_EDITORS: [(&str, &[&str]); 11] = [
+    ("code", &["--goto", "{path}:{line}"]),
+    ("code-oss", &["--goto", "{path}:{line}"]),
+    ("codium", &["--goto", "{path}:{line}"]),
+    ("subl", &["{path}:{line}"]),
+    ("zed", &["{path}:{line}"]),
+    ("gedit", &["+{line}", "{path}"]),
+    ("emacs", &["+{line}", "{path}"]),
+    ("gvim", &["+{line}", "{path}"]),
+    ("kate", &["--line", "{line}", "{path}"]),
+    ("kwrite", &["--line", "{line}", "{path}"]),
+    ("geany", &["--line", "{line}", "{path}"]),
+];
+
 #[derive(Debug, Clone)]
 pub enum DefaultApplicationType {
     FileExplorer,
@@ -13,16 +68,75 @@ pub enum DefaultApplicationType {
     Mime(String),
 }
 
+impl DefaultApplicationType {
+    pub fn scheme_handler(scheme: &str) -> Self {
+        Self::Mime(format!("{}{}", SCHEME_HANDLER_MIMETYPE_PREFIX, scheme))
+    }
+}
+
 #[derive(Debug)]
 pub struct SysInfoLoader {
     pub locales: Vec<String>,
     pub desktop_entries: Vec<DesktopEntry>,
+    // command prefix to run something inside a terminal emulator
+    pub terminal: Option<Vec<String>>,
+    // whether each MIME type (or scheme handler) has a default app, every lookup runs xdg-mime
+    default_apps: RefCell<HashMap<String, bool>>,
 }
 
 pub enum FileOpenError {
     DefaultAppNotFound,
 }
 
+#[derive(Debug, Clone)]
+pub struct Battery {
+    pub name: String,
+    pub model: Option<String>,
+    pub capacity: u8,
+    // "Charging", "Discharging", "Full"...
+    pub status: String,
+    // until empty when discharging or until full when charging
+    pub time_left: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryInfo {
+    pub total: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub total: u64,
+    pub available: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadAverage {
+    pub one: f32,
+    pub five: f32,
+    pub fifteen: f32,
+    pub running: u32,
+    pub tasks: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfaceAddress {
+    pub interface: String,
+    pub address: IpAddr,
+}
+
+#[derive(Debug, Clone)]
+pub struct DefaultRoute {
+    pub interface: String,
+    pub gateway: IpAddr,
+}
+
 impl SysInfoLoader {
     pub fn new() -> Self {
         let locales = get_languages_from_env();
@@ -32,7 +146,29 @@ impl SysInfoLoader {
         Self {
             locales,
             desktop_entries,
+            terminal: SysInfoLoader::find_terminal(),
+            default_apps: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // $TERMINAL takes precedence, but it doesn't say how to pass a command,
+    // "-e" is the most widely supported option
+    fn find_terminal() -> Option<Vec<String>> {
+        if let Ok(terminal) = env::var("TERMINAL")
+            && !terminal.is_empty()
+            && fsutil::find_in_path(&terminal).is_some()
+        {
+            return Some(vec![terminal, "-e".to_owned()]);
         }
+
+        TERMINALS
+            .iter()
+            .find(|(name, _)| fsutil::find_in_path(name).is_some())
+            .map(|(name, args)| {
+                let mut cmd = vec![name.to_string()];
+                cmd.extend(args.iter().map(|it| it.to_string()));
+                cmd
+            })
     }
 
     pub fn get_default_app_cmd(&self, app_type: &DefaultApplicationType) -> String {
@@ -52,6 +188,54 @@ impl SysInfoLoader {
         app_cmd
     }
 
+    /// Command to open a file with the default application for its MIME type,
+    /// when there is one
+    pub fn get_file_open_cmd(&self, path: &str) -> Option<Vec<String>> {
+        let mime = match SysInfoLoader::try_get_file_mime_type_str(path)? {
+            DefaultApplicationType::Mime(mime) => mime,
+            _ => return None,
+        };
+        let mut app_cmd = SysInfoLoader::cmd_str(&query_default_app(&mime).ok()?);
+        app_cmd.push(path.to_string());
+        Some(app_cmd)
+    }
+
+    /// Command to open a text file at a given line with the default application for it,
+    /// when that is an editor known to support it, otherwise the file is just opened
+    pub fn get_open_at_line_cmd(&self, path: &str, line: usize) -> Option<Vec<String>> {
+        let mime = match SysInfoLoader::try_get_file_mime_type_str(path)? {
+            DefaultApplicationType::Mime(mime) => mime,
+            _ => return None,
+        };
+        let mut app_cmd = SysInfoLoader::cmd_str(&query_default_app(&mime).ok()?);
+
+        let binary = Path::new(app_cmd.first()?)
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+        match LINE_AWARE_EDITORS.iter().find(|(name, _)| *name == binary) {
+            Some((_, args)) => app_cmd.extend(args.iter().map(|arg| {
+                arg.replace("{path}", path).replace("{line}", &line.to_string())
+            })),
+            None => app_cmd.push(path.to_string()),
+        }
+
+        Some(app_cmd)
+    }
+
+    pub fn has_default_app(&self, mime: &str) -> bool {
+        *self
+            .default_apps
+            .borrow_mut()
+            .entry(mime.to_string())
+            .or_insert_with(|| query_default_app(mime).is_ok_and(|app| !app.is_empty()))
+    }
+
+    /// Whether an app opens URLs with this scheme, asked for while typing so it's cached too
+    pub fn has_scheme_handler(&self, scheme: &str) -> bool {
+        self.has_default_app(&format!("{}{}", SCHEME_HANDLER_MIMETYPE_PREFIX, scheme))
+    }
+
     pub fn try_get_file_mime_type_str(path: &str) -> Option<DefaultApplicationType> {
         match query_mime_info(path) {
             Ok(mime) => Some(DefaultApplicationType::Mime(
@@ -94,3 +278,234 @@ impl SysInfoLoader {
         entry
     }
 }
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|it| it.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+pub fn read_batteries() -> Vec<Battery> {
+    let entries = match fs::read_dir(POWER_SUPPLY_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut batteries: Vec<Battery> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| read_trimmed(&dir.join("type")).as_deref() == Some("Battery"))
+        // peripherals like mice also show up as batteries
+        .filter(|dir| read_trimmed(&dir.join("scope")).as_deref() != Some("Device"))
+        .filter_map(|dir| {
+            let status = read_trimmed(&dir.join("status")).unwrap_or("Unknown".to_owned());
+
+            // some report energy in µWh and power in µW, others charge in µAh and current in µA
+            let (now, full, rate) = match read_number(&dir.join("energy_now")) {
+                Some(now) => (
+                    now,
+                    read_number(&dir.join("energy_full")),
+                    read_number(&dir.join("power_now")),
+                ),
+                None => (
+                    read_number(&dir.join("charge_now"))?,
+                    read_number(&dir.join("charge_full")),
+                    read_number(&dir.join("current_now")),
+                ),
+            };
+            let hours_left = match (status.as_str(), full, rate) {
+                (_, _, None | Some(0)) => None,
+                ("Discharging", _, Some(rate)) => Some(now as f64 / rate as f64),
+                ("Charging", Some(full), Some(rate)) => Some(full.saturating_sub(now) as f64 / rate as f64),
+                _ => None,
+            };
+
+            Some(Battery {
+                name: dir.file_name()?.to_string_lossy().to_string(),
+                model: read_trimmed(&dir.join("model_name")).filter(|it| !it.is_empty()),
+                capacity: read_number(&dir.join("capacity"))? as u8,
+                status,
+                time_left: hours_left.map(|hours| Duration::from_secs_f64(hours * 3600.0)),
+            })
+        })
+        .collect();
+    batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    batteries
+}
+
+pub fn read_memory() -> Option<MemoryInfo> {
+    let meminfo = fs::read_to_string(MEMINFO_FILE).ok()?;
+    // values are in kB
+    let field = |key: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+
+    Some(MemoryInfo {
+        total: field("MemTotal")?,
+        available: field("MemAvailable")?,
+        swap_total: field("SwapTotal").unwrap_or(0),
+        swap_free: field("SwapFree").unwrap_or(0),
+    })
+}
+
+/// Usage of the mounted block devices, each device only once
+pub fn read_disks() -> Vec<DiskUsage> {
+    let mounts = fs::read_to_string(MOUNTS_FILE).unwrap_or_default();
+    let mut disks: Vec<DiskUsage> = vec![];
+
+    for line in mounts.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (device, mount_point, fs_type) = match fields[..] {
+            [device, mount_point, fs_type, ..] => (device, mount_point, fs_type),
+            _ => continue,
+        };
+        if !device.starts_with("/dev/")
+            || HIDDEN_FILESYSTEMS.contains(&fs_type)
+            || disks.iter().any(|it| it.device == device)
+        {
+            continue;
+        }
+
+        // spaces and such are escaped in octal, like "\040"
+        let mount_point = mount_point.replace("\\040", " ").replace("\\011", "\t");
+        if let Some((total, available)) = statvfs(&mount_point) {
+            disks.push(DiskUsage {
+                device: device.to_string(),
+                mount_point,
+                fs_type: fs_type.to_string(),
+                total,
+                available,
+            });
+        }
+    }
+
+    disks
+}
+
+// total and available to unprivileged users, in bytes
+fn statvfs(path: &str) -> Option<(u64, u64)> {
+    let path = CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let block_size = stat.f_frsize as u64;
+    Some((stat.f_blocks as u64 * block_size, stat.f_bavail as u64 * block_size))
+}
+
+pub fn read_uptime() -> Option<Duration> {
+    let seconds: f64 = read_trimmed(Path::new(UPTIME_FILE))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs_f64(seconds))
+}
+
+// "0.52 0.61 0.70 2/812 12345"
+pub fn read_load() -> Option<LoadAverage> {
+    let loadavg = read_trimmed(Path::new(LOADAVG_FILE))?;
+    let fields: Vec<&str> = loadavg.split_whitespace().collect();
+    let (running, tasks) = fields.get(3)?.split_once('/')?;
+
+    Some(LoadAverage {
+        one: fields.first()?.parse().ok()?,
+        five: fields.get(1)?.parse().ok()?,
+        fifteen: fields.get(2)?.parse().ok()?,
+        running: running.parse().ok()?,
+        tasks: tasks.parse().ok()?,
+    })
+}
+
+/// Addresses of the interfaces that are up, without loopback
+pub fn read_addresses() -> Vec<InterfaceAddress> {
+    let mut addresses: Vec<InterfaceAddress> = vec![];
+    let mut ifaddrs: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut ifaddrs) } != 0 {
+        println!("unable to list network addresses: {}", std::io::Error::last_os_error());
+        return addresses;
+    }
+
+    let mut current = ifaddrs;
+    while let Some(it) = unsafe { current.as_ref() } {
+        current = it.ifa_next;
+
+        let flags = it.ifa_flags as i32;
+        if it.ifa_addr.is_null() || flags & libc::IFF_UP == 0 || flags & libc::IFF_LOOPBACK != 0 {
+            continue;
+        }
+
+        let address = unsafe {
+            match (*it.ifa_addr).sa_family as i32 {
+                libc::AF_INET => {
+                    let addr = &*(it.ifa_addr as *const libc::sockaddr_in);
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)))
+                }
+                libc::AF_INET6 => {
+                    let addr = &*(it.ifa_addr as *const libc::sockaddr_in6);
+                    IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr))
+                }
+                _ => continue,
+            }
+        };
+
+        addresses.push(InterfaceAddress {
+            interface: unsafe { CStr::from_ptr(it.ifa_name) }
+                .to_string_lossy()
+                .to_string(),
+            address,
+        });
+    }
+
+    unsafe { libc::freeifaddrs(ifaddrs) };
+    addresses
+}
+
+/// The default IPv4 and IPv6 gateways
+pub fn read_default_routes() -> Vec<DefaultRoute> {
+    let mut routes: Vec<DefaultRoute> = vec![];
+
+    // "Iface Destination Gateway ...", addresses are little endian hex
+    let ipv4_routes = fs::read_to_string(IPV4_ROUTE_FILE).unwrap_or_default();
+    for line in ipv4_routes.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if let [interface, "00000000", gateway, ..] = fields[..] {
+            // routes straight to a device (VPNs, point to point links) have no gateway
+            let gateway = match u32::from_str_radix(gateway, 16) {
+                Ok(gateway) if gateway != 0 => gateway,
+                _ => continue,
+            };
+            routes.push(DefaultRoute {
+                interface: interface.to_string(),
+                gateway: IpAddr::V4(Ipv4Addr::from(u32::from_be(gateway))),
+            });
+        }
+    }
+
+    // "dest dest_len src src_len gateway metric refcnt use flags iface", in plain hex
+    let ipv6_routes = fs::read_to_string(IPV6_ROUTE_FILE).unwrap_or_default();
+    for line in ipv6_routes.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if let [dest, "00", _, _, gateway, .., interface] = fields[..] {
+            let gateway = match u128::from_str_radix(gateway, 16) {
+                Ok(gateway) if gateway != 0 => gateway,
+                _ => continue,
+            };
+            if u128::from_str_radix(dest, 16) == Ok(0) && interface != "lo" {
+                routes.push(DefaultRoute {
+                    interface: interface.to_string(),
+                    gateway: IpAddr::V6(Ipv6Addr::from(gateway)),
+                });
+            }
+        }
+    }
+
+    routes
+}
@@ -0,0 +1,322 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use regex::Regex;
+use tz::TimeZone;
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+// folders in the zoneinfo dir that duplicate the main database
+const ZONEINFO_IGNORED_DIRS: [&str; 2] = ["posix", "right"];
+
+// common abbreviations, they are ambiguous and don't follow DST,
+// but are what people type when converting times
+const ZONE_ABBREVIATIONS: [(&str, i32); 24] = [
+    ("UTC", 0),
+    ("GMT", 0),
+    ("Z", 0),
+    ("WET", 0),
+    ("BST", 3600),
+    ("CET", 3600),
+    ("CEST", 2 * 3600),
+    ("EET", 2 * 3600),
+    ("EEST", 3 * 3600),
+    ("MSK", 3 * 3600),
+    ("IST", 5 * 3600 + 1800),
+    ("SGT", 8 * 3600),
+    ("CST", -6 * 3600),
+    ("JST", 9 * 3600),
+    ("KST", 9 * 3600),
+    ("AEST", 10 * 3600),
+    ("AEDT", 11 * 3600),
+    ("EST", -5 * 3600),
+    ("EDT", -4 * 3600),
+    ("CDT", -5 * 3600),
+    ("MST", -7 * 3600),
+    ("MDT", -6 * 3600),
+    ("PST", -8 * 3600),
+    ("PDT", -7 * 3600),
+];
+
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub label: String,
+    tz: TimeZone,
+}
+
+#[derive(Debug, Clone)]
+pub enum TimeQuery {
+    // an instant, and zones the user explicitly asked to see it in
+    Instant(DateTime<Utc>, Vec<Zone>),
+    Epoch(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ZonedTime {
+    pub label: String,
+    pub time: DateTime<FixedOffset>,
+    pub designation: String,
+}
+
+impl Zone {
+    pub fn local() -> Self {
+        Self {
+            label: "Local".to_owned(),
+            tz: TimeZone::local().unwrap_or_else(|_| TimeZone::utc()),
+        }
+    }
+
+    pub fn utc() -> Self {
+        Self {
+            label: "UTC".to_owned(),
+            tz: TimeZone::utc(),
+        }
+    }
+
+    /// Resolves zone names like "Europe/Berlin", "CET", "PST" or a city like "new york"
+    pub fn resolve(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        if name.eq_ignore_ascii_case("local") {
+            return Some(Self::local());
+        }
+
+        // zones present in the database (like "CET") know about DST,
+        // so they take precedence over the fixed abbreviations
+        let exact_match = is_zone_file(name);
+        if !exact_match
+            && let Some((abbreviation, offset)) = ZONE_ABBREVIATIONS
+                .iter()
+                .find(|(abbreviation, _)| abbreviation.eq_ignore_ascii_case(name))
+        {
+            return Some(Self {
+                label: abbreviation.to_string(),
+                tz: TimeZone::fixed(*offset).ok()?,
+            });
+        }
+
+        let zone_name = find_zone_name(name)?;
+        let data = fs::read(Path::new(ZONEINFO_DIR).join(&zone_name)).ok()?;
+        Some(Self {
+            label: zone_name,
+            tz: TimeZone::from_tz_data(&data).ok()?,
+        })
+    }
+
+    pub fn at(&self, t: &DateTime<Utc>) -> ZonedTime {
+        let (offset, designation) = match self.tz.find_local_time_type(t.timestamp()) {
+            Ok(local_type) => (
+                local_type.ut_offset(),
+                local_type.time_zone_designation().to_string(),
+            ),
+            Err(_) => (0, "UTC".to_owned()),
+        };
+        let offset = FixedOffset::east_opt(offset).unwrap_or(FixedOffset::east_opt(0).unwrap());
+
+        ZonedTime {
+            label: self.label.clone(),
+            time: t.with_timezone(&offset),
+            designation,
+        }
+    }
+
+    // wall clock time in this zone to the instant it represents
+    fn to_utc(&self, naive: &NaiveDateTime) -> DateTime<Utc> {
+        let as_utc = naive.and_utc();
+        let first_guess = as_utc - chrono::Duration::seconds(self.offset_secs(&as_utc) as i64);
+        as_utc - chrono::Duration::seconds(self.offset_secs(&first_guess) as i64)
+    }
+
+    fn offset_secs(&self, t: &DateTime<Utc>) -> i32 {
+        self.tz
+            .find_local_time_type(t.timestamp())
+            .map(|local_type| local_type.ut_offset())
+            .unwrap_or(0)
+    }
+}
+
+impl ZonedTime {
+    pub fn display(&self) -> String {
+        format!(
+            "{} {} ({})",
+            self.time.format("%Y-%m-%d %H:%M:%S"),
+            self.designation,
+            self.time.format("%a")
+        )
+    }
+
+    pub fn offset_display(&self) -> String {
+        format!("UTC{}", self.time.format("%:z"))
+    }
+}
+
+/// Parses things like "1700000000", "now in Tokyo", "15:00 PST to CET" or "now as epoch"
+pub fn parse_query(input: &str) -> Option<TimeQuery> {
+    let input = input.trim();
+    let lowercase = input.to_lowercase();
+
+    if lowercase == "epoch" || lowercase == "now as epoch" || lowercase == "now in epoch" {
+        return Some(TimeQuery::Epoch(Utc::now()));
+    }
+
+    let split_regex = Regex::new(r"(?i)^(.+?)\s+(?:in|to|as)\s+(.+)$").unwrap();
+    if let Some(captures) = split_regex.captures(input) {
+        let source = captures.get(1).unwrap().as_str();
+        let target = Zone::resolve(captures.get(2).unwrap().as_str());
+        if let (Some(t), Some(zone)) = (parse_source(source), target) {
+            return Some(TimeQuery::Instant(t, vec![zone]));
+        }
+    }
+
+    parse_source(input).map(|t| TimeQuery::Instant(t, vec![]))
+}
+
+/// Interprets a digits-only value as a unix timestamp, guessing its unit by the magnitude
+pub fn parse_timestamp(input: &str) -> Option<DateTime<Utc>> {
+    let (integer, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let digits = integer.trim_start_matches('-');
+    if digits.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let value: i64 = integer.parse().ok()?;
+    match digits.len() {
+        9 | 10 => {
+            let nanos = format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()?;
+            DateTime::from_timestamp(value, nanos)
+        }
+        12 | 13 if fraction.is_empty() => DateTime::from_timestamp_millis(value),
+        15 | 16 if fraction.is_empty() => DateTime::from_timestamp_micros(value),
+        18 | 19 if fraction.is_empty() => Some(DateTime::from_timestamp_nanos(value)),
+        _ => None,
+    }
+}
+
+// the time part of a query: "now", a timestamp, a date or a wall time in some zone
+fn parse_source(input: &str) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("now") {
+        return Some(Utc::now());
+    }
+
+    if let Some(t) = parse_timestamp(input) {
+        return Some(t);
+    }
+
+    if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+        return Some(t.with_timezone(&Utc));
+    }
+
+    if let Ok(t) = DateTime::parse_from_rfc2822(input) {
+        return Some(t.with_timezone(&Utc));
+    }
+
+    // ISO-8601 without an offset is taken as local time
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return Some(Zone::local().to_utc(&naive));
+        }
+    }
+
+    parse_wall_time(input)
+}
+
+// "15:00 PST", "3pm Tokyo" or "9:30" (local), on today's date in that zone
+fn parse_wall_time(input: &str) -> Option<DateTime<Utc>> {
+    let time_regex =
+        Regex::new(r"(?i)^(\d{1,2})(?::(\d{2}))?(?::(\d{2}))?\s*(am|pm)?(?:\s+(.+))?$").unwrap();
+    let captures = time_regex.captures(input)?;
+
+    // a lone number is not a time
+    if captures.get(2).is_none() && captures.get(4).is_none() {
+        return None;
+    }
+
+    let mut hour: u32 = captures[1].parse().ok()?;
+    let minute: u32 = captures.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    let second: u32 = captures.get(3).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    if let Some(meridiem) = captures.get(4) {
+        if hour == 0 || hour > 12 {
+            return None;
+        }
+        let is_pm = meridiem.as_str().eq_ignore_ascii_case("pm");
+        hour = match (hour, is_pm) {
+            (12, false) => 0,
+            (12, true) => 12,
+            (h, true) => h + 12,
+            (h, false) => h,
+        };
+    }
+
+    let zone = match captures.get(5) {
+        Some(name) => Zone::resolve(name.as_str())?,
+        None => Zone::local(),
+    };
+
+    let today: NaiveDate = zone.at(&Utc::now()).time.date_naive();
+    let time = NaiveTime::from_hms_opt(hour, minute, second)?;
+    Some(zone.to_utc(&today.and_time(time)))
+}
+
+// joining an absolute path would replace the zoneinfo dir, so those are never zones
+fn is_zone_file(name: &str) -> bool {
+    !name.contains("..") && !name.starts_with('/') && Path::new(ZONEINFO_DIR).join(name).is_file()
+}
+
+fn find_zone_name(name: &str) -> Option<String> {
+    if is_zone_file(name) {
+        return Some(name.to_string());
+    }
+
+    let wanted = name.replace(' ', "_").to_lowercase();
+    zone_names()
+        .iter()
+        .find(|zone| {
+            zone.to_lowercase() == wanted
+                || zone
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|city| city.to_lowercase() == wanted)
+        })
+        .cloned()
+}
+
+// every zone in the system database, like "America/Sao_Paulo"
+fn zone_names() -> &'static Vec<String> {
+    static ZONE_NAMES: OnceLock<Vec<String>> = OnceLock::new();
+    ZONE_NAMES.get_or_init(|| {
+        let mut names = Vec::new();
+        collect_zone_names(Path::new(ZONEINFO_DIR), &PathBuf::new(), &mut names);
+        names.sort();
+        names
+    })
+}
+
+fn collect_zone_names(dir: &Path, prefix: &Path, names: &mut Vec<String>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if path.is_dir() {
+            if !ZONEINFO_IGNORED_DIRS.contains(&file_name.as_str()) {
+                collect_zone_names(&path, &prefix.join(&file_name), names);
+            }
+        } else if file_name.chars().next().is_some_and(|c| c.is_ascii_uppercase()) {
+            names.push(prefix.join(&file_name).to_string_lossy().to_string());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    env, fmt, fs,
+    io::{self, Write},
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+
+use crate::urlutil;
+
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+const STICKY_BIT: u32 = 0o1000;
+
+#[derive(Debug)]
+pub enum TrashError {
+    NotFound,
+    NoTrashDir,
+    Io(io::Error),
+}
+
+impl fmt::Display for TrashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrashError::NotFound => write!(f, "file does not exist"),
+            TrashError::NoTrashDir => write!(f, "no trash directory available for this file"),
+            TrashError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for TrashError {
+    fn from(e: io::Error) -> Self {
+        TrashError::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, TrashError>;
+
+/// Moves a file or folder to the trash following the FreeDesktop trash spec:
+/// the home trash for files on the same device as it, otherwise the
+/// `.Trash/$uid` or `.Trash-$uid` dir on the top of the file's mount point.
+pub fn trash(path: &Path) -> Result<()> {
+    let path = absolute_path(path)?;
+    let metadata = fs::symlink_metadata(&path).map_err(|_| TrashError::NotFound)?;
+    let (trash_dir, topdir) = trash_dir_for(&path, metadata.dev())?;
+
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+
+    // the original path is relative to the top dir when not using the home trash
+    let original_path = match &topdir {
+        Some(topdir) => path.strip_prefix(topdir).unwrap_or(&path),
+        None => &path,
+    };
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        urlutil::encode_path(&original_path.to_string_lossy()),
+        Local::now().format("%Y-%m-%dT%H:%M:%S"),
+    );
+
+    let file_name = path
+        .file_name()
+        .ok_or(TrashError::NotFound)?
+        .to_string_lossy()
+        .to_string();
+    let (name, info_path) = reserve_name(&info_dir, &file_name)?;
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&info_path)?
+        .write_all(info.as_bytes())?;
+
+    if let Err(e) = fs::rename(&path, files_dir.join(&name)) {
+        let _ = fs::remove_file(&info_path);
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+// only the parent is resolved, a symlink itself is trashed instead of what it points to
+fn absolute_path(path: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name().ok_or(TrashError::NotFound)?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let parent = fs::canonicalize(parent).map_err(|_| TrashError::NotFound)?;
+    Ok(parent.join(file_name))
+}
+
+// the .trashinfo file is created exclusively, so it works as a lock on the name
+fn reserve_name(info_dir: &Path, file_name: &str) -> Result<(String, PathBuf)> {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (file_name, String::new()),
+    };
+
+    for i in 1.. {
+        let name = if i == 1 {
+            file_name.to_string()
+        } else {
+            format!("{}.{}{}", stem, i, extension)
+        };
+        let info_path = info_dir.join(format!("{}.{}", name, TRASH_INFO_EXTENSION));
+        match fs::OpenOptions::new().write(true).create_new(true).open(&info_path) {
+            Ok(_) => return Ok((name, info_path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    unreachable!("there's always a free name")
+}
+
+fn home_trash_dir() -> PathBuf {
+    let data_home = match env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home_path = env::var("HOME").expect("expected $HOME to always be defined");
+            PathBuf::from(home_path).join(".local/share")
+        }
+    };
+
+    data_home.join("Trash")
+}
+
+// returns the trash dir and, when it isn't the home trash, the top dir it belongs to
+fn trash_dir_for(path: &Path, device: u64) -> Result<(PathBuf, Option<PathBuf>)> {
+    let home_trash = home_trash_dir();
+    let home_trash_device = home_trash
+        .ancestors()
+        .find_map(|it| fs::metadata(it).ok())
+        .map(|m| m.dev());
+    if home_trash_device == Some(device) {
+        return Ok((home_trash, None));
+    }
+
+    let topdir = mount_point(path, device);
+    let uid = fs::metadata("/proc/self")?.uid();
+
+    // $topdir/.Trash is only usable if the admin created it properly
+    let admin_trash = topdir.join(".Trash");
+    let is_valid_admin_trash = fs::symlink_metadata(&admin_trash).is_ok_and(|m| {
+        m.is_dir() && !m.file_type().is_symlink() && m.permissions().mode() & STICKY_BIT != 0
+    });
+    if is_valid_admin_trash {
+        let user_trash = admin_trash.join(uid.to_string());
+        if fs::create_dir_all(&user_trash).is_ok() {
+            return Ok((user_trash, Some(topdir)));
+        }
+    }
+
+    let user_trash = topdir.join(format!(".Trash-{}", uid));
+    if fs::create_dir_all(&user_trash).is_ok() {
+        let _ = fs::set_permissions(&user_trash, fs::Permissions::from_mode(0o700));
+        return Ok((user_trash, Some(topdir)));
+    }
+
+    Err(TrashError::NoTrashDir)
+}
+
+// the highest ancestor that is still on the same device as the file
+fn mount_point(path: &Path, device: u64) -> PathBuf {
+    let mut topdir = path.to_path_buf();
+    for ancestor in path.ancestors().skip(1) {
+        match fs::metadata(ancestor) {
+            Ok(m) if m.dev() == device => topdir = ancestor.to_path_buf(),
+            _ => break,
+        }
+    }
+
+    topdir
+}
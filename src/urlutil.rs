@@ -0,0 +1,121 @@
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use regex::Regex;
+
+// paths in URLs escape everything but unreserved characters and slashes
+const PATH_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// schemes that are recognized even without checking for a registered handler
+const KNOWN_SCHEMES: [&str; 8] = ["http", "https", "ftp", "mailto", "ssh", "file", "tel", "sftp"];
+
+// bare domains are only recognized with these TLDs, otherwise file names
+// like "main.rs" or "notes.md" would be taken as domains
+const KNOWN_TLDS: [&str; 34] = [
+    "com", "org", "net", "io", "dev", "app", "edu", "gov", "info", "xyz", "ai", "co", "me",
+    "tv", "cloud", "tech", "site", "online", "br", "uk", "de", "fr", "jp", "nl", "it", "es", "ca",
+    "au", "us", "eu", "ch", "se", "pl", "in",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedUrl {
+    pub url: String,
+    pub scheme: String,
+}
+
+/// Recognizes inputs that look like URLs, like "https://…", "github.com/rust-lang",
+/// "192.168.0.1:8080" or "mailto:someone@example.com".
+///
+/// `has_handler` is asked about schemes that aren't well known, so only the ones
+/// with an application registered to open them are recognized.
+pub fn detect(input: &str, has_handler: impl Fn(&str) -> bool) -> Option<DetectedUrl> {
+    let input = input.trim();
+    if input.is_empty() || input.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if let Some(url) = detect_host(input) {
+        return Some(url);
+    }
+
+    let scheme_regex = Regex::new(r"^([a-zA-Z][a-zA-Z0-9+.-]*):(.+)$").unwrap();
+    let captures = scheme_regex.captures(input)?;
+    let scheme = captures[1].to_lowercase();
+    let rest = &captures[2];
+
+    let is_known = KNOWN_SCHEMES.contains(&scheme.as_str());
+    if !is_known && !has_handler(&scheme) {
+        return None;
+    }
+
+    // hierarchical schemes need something after the "//"
+    if rest.starts_with("//") && rest.len() <= 2 {
+        return None;
+    }
+
+    Some(DetectedUrl {
+        url: input.to_string(),
+        scheme,
+    })
+}
+
+// "github.com/rust-lang", "localhost:3000" or "10.0.0.1:8080/status", without a scheme
+fn detect_host(input: &str) -> Option<DetectedUrl> {
+    let host_regex = Regex::new(
+        r"^(?i)(localhost|(?:\d{1,3}\.){3}\d{1,3}|\[[0-9a-f:]+\]|(?:[a-z0-9-]+\.)+([a-z]{2,}))(:\d{1,5})?([/?#].*)?$",
+    )
+    .unwrap();
+    let captures = host_regex.captures(input)?;
+
+    let host = captures.get(1)?.as_str();
+    let is_domain = captures.get(2).is_some();
+    if is_domain {
+        let tld = captures[2].to_lowercase();
+        if !KNOWN_TLDS.contains(&tld.as_str()) {
+            return None;
+        }
+    } else if host.contains('.') && !host.starts_with('[') {
+        // IPv4, every part has to fit a byte
+        if host.split('.').any(|part| part.parse::<u8>().is_err()) {
+            return None;
+        }
+    }
+
+    // local addresses rarely have TLS set up
+    let scheme = if is_domain { "https" } else { "http" };
+    Some(DetectedUrl {
+        url: format!("{}://{}", scheme, input),
+        scheme: scheme.to_owned(),
+    })
+}
+
+/// The lowercase scheme of a URL, "mailto" for "mailto:{to}"
+pub fn scheme(url: &str) -> Option<String> {
+    let (scheme, _) = url.split_once(':')?;
+    let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '.' || c == '-');
+    is_scheme.then(|| scheme.to_lowercase())
+}
+
+/// The local path a file:// URL points to
+pub fn file_url_path(url: &str) -> Option<String> {
+    let path = url.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()
+        .map(|it| it.to_string())
+}
+
+pub fn encode_path(path: &str) -> String {
+    utf8_percent_encode(path, PATH_ENCODE_SET).to_string()
+}
+
+pub fn file_url(path: &str) -> String {
+    format!("file://{}", encode_path(path))
+}
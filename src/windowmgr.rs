@@ -0,0 +1,697 @@
+use std::{
+    env, fmt,
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_json::Value;
+use wayland_client::{
+    Connection, Dispatch, QueueHandle, event_created_child,
+    protocol::{wl_registry, wl_seat::WlSeat},
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use crate::APPLICATION_ID;
+
+// typing queries the window list a lot, it doesn't change that fast
+const WINDOW_LIST_TTL: Duration = Duration::from_secs(2);
+const IPC_TIMEOUT: Duration = Duration::from_secs(1);
+
+const I3_IPC_MAGIC: &[u8] = b"i3-ipc";
+const I3_RUN_COMMAND: u32 = 0;
+const I3_GET_TREE: u32 = 4;
+
+const FOREIGN_TOPLEVEL_MANAGER_VERSION: u32 = 3;
+
+#[derive(Debug)]
+pub enum WindowError {
+    NoCompositor,
+    NotFound,
+    Io(io::Error),
+    Protocol(String),
+    Wayland(String),
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::NoCompositor => write!(f, "no supported compositor found"),
+            WindowError::NotFound => write!(f, "window is not open anymore"),
+            WindowError::Io(e) => write!(f, "{}", e),
+            WindowError::Protocol(msg) => write!(f, "unexpected IPC reply: {}", msg),
+            WindowError::Wayland(msg) => write!(f, "wayland error: {}", msg),
+        }
+    }
+}
+
+impl From<io::Error> for WindowError {
+    fn from(e: io::Error) -> Self {
+        WindowError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for WindowError {
+    fn from(e: serde_json::Error) -> Self {
+        WindowError::Protocol(e.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, WindowError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowId {
+    // the container id, for sway and i3
+    Con(i64),
+    // the client address in Hyprland, like "0x55d0a4c3e2f0"
+    Address(String),
+    // toplevels have no id, they are found again by their position in the list
+    Toplevel(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+    pub id: WindowId,
+    // the wayland app id or the X11 class
+    pub app_id: String,
+    pub title: String,
+}
+
+impl Window {
+    /// Whether the window was opened by an app known by any of these names,
+    /// desktop file ids are compared by their last part too ("org.gnome.Nautilus")
+    pub fn belongs_to(&self, app_names: &[String]) -> bool {
+        let app_id = self.app_id.to_lowercase();
+        if app_id.is_empty() {
+            return false;
+        }
+
+        app_names.iter().any(|name| {
+            let name = name.to_lowercase();
+            name == app_id || name.rsplit('.').next() == Some(app_id.as_str())
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    I3(PathBuf),
+    Hyprland(PathBuf),
+    ForeignToplevel,
+}
+
+impl Backend {
+    fn detect() -> Option<Self> {
+        if let Some(socket) = env::var_os("SWAYSOCK").or_else(|| env::var_os("I3SOCK")) {
+            return Some(Backend::I3(PathBuf::from(socket)));
+        }
+
+        if let Ok(signature) = env::var("HYPRLAND_INSTANCE_SIGNATURE") {
+            // older versions kept it in /tmp
+            let runtime_dir = env::var("XDG_RUNTIME_DIR").unwrap_or("/tmp".to_owned());
+            let socket = [runtime_dir.as_str(), "/tmp"]
+                .iter()
+                .map(|dir| Path::new(dir).join("hypr").join(&signature).join(".socket.sock"))
+                .find(|path| path.exists())?;
+            return Some(Backend::Hyprland(socket));
+        }
+
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            return Some(Backend::ForeignToplevel);
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+struct WindowList {
+    windows: Vec<Window>,
+    // when the windows were listed, None once they are known to be stale
+    listed_at: Option<Instant>,
+    refreshing: bool,
+}
+
+/// Lists and focuses open windows through whatever the compositor offers,
+/// sway/i3 IPC, Hyprland's socket or the wlr foreign toplevel protocol.
+///
+/// The list is refreshed in a background thread, a slow compositor never blocks typing,
+/// `updated` is set when a refresh changed it.
+#[derive(Debug)]
+pub struct WindowMgr {
+    backend: Option<Backend>,
+    list: Arc<Mutex<WindowList>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl WindowMgr {
+    pub fn new(updated: Arc<AtomicBool>) -> Self {
+        let window_mgr = Self {
+            backend: Backend::detect(),
+            list: Arc::new(Mutex::new(WindowList::default())),
+            updated,
+        };
+        window_mgr.windows();
+        window_mgr
+    }
+
+    /// The last listed windows, a refresh is started when they are older than the TTL
+    pub fn windows(&self) -> Vec<Window> {
+        let mut list = self.list.lock().expect("WindowMgr poisoned");
+        let is_stale = list
+            .listed_at
+            .is_none_or(|listed_at| listed_at.elapsed() >= WINDOW_LIST_TTL);
+        if is_stale && !list.refreshing && self.backend.is_some() {
+            list.refreshing = true;
+            self.refresh_in_background();
+        }
+        list.windows.clone()
+    }
+
+    pub fn find_app_window(&self, app_names: &[String]) -> Option<Window> {
+        self.windows()
+            .into_iter()
+            .find(|window| window.belongs_to(app_names))
+    }
+
+    pub fn focus(&self, window: &Window) {
+        let result = match (&self.backend, &window.id) {
+            (Some(Backend::I3(socket)), WindowId::Con(id)) => {
+                i3_request(socket, I3_RUN_COMMAND, &format!("[con_id={}] focus", id)).map(|_| ())
+            }
+            (Some(Backend::Hyprland(socket)), WindowId::Address(address)) => {
+                hyprland_request(socket, &format!("dispatch focuswindow address:{}", address))
+                    .map(|_| ())
+            }
+            (Some(Backend::ForeignToplevel), WindowId::Toplevel(index)) => {
+                activate_toplevel(*index, window)
+            }
+            _ => Err(WindowError::NoCompositor),
+        };
+
+        // the list is stale once the focus moves
+        self.list.lock().expect("WindowMgr poisoned").listed_at = None;
+        if let Err(e) = result {
+            println!("unable to focus window '{}': {}", window.title, e);
+        }
+    }
+
+    fn refresh_in_background(&self) {
+        let backend = self.backend.clone();
+        let list = self.list.clone();
+        let updated = self.updated.clone();
+        thread::spawn(move || {
+            let windows = match list_windows(backend.as_ref()) {
+                Ok(windows) => windows,
+                Err(e) => {
+                    println!("unable to list windows: {}", e);
+                    vec![]
+                }
+            };
+
+            let mut list = list.lock().expect("WindowMgr poisoned");
+            list.listed_at = Some(Instant::now());
+            list.refreshing = false;
+            if list.windows != windows {
+                list.windows = windows;
+                updated.store(true, Ordering::Relaxed);
+            }
+        });
+    }
+}
+
+fn list_windows(backend: Option<&Backend>) -> Result<Vec<Window>> {
+    match backend {
+        Some(Backend::I3(socket)) => {
+            let tree = i3_request(socket, I3_GET_TREE, "")?;
+            let mut windows = vec![];
+            collect_i3_windows(&tree, &mut windows);
+            Ok(windows)
+        }
+        Some(Backend::Hyprland(socket)) => list_hyprland_windows(socket),
+        Some(Backend::ForeignToplevel) => Ok(list_toplevels()?
+            .state
+            .toplevels
+            .into_iter()
+            .enumerate()
+            .filter(|(_, it)| it.app_id != APPLICATION_ID)
+            .map(|(index, it)| Window {
+                id: WindowId::Toplevel(index),
+                app_id: it.app_id,
+                title: it.title,
+            })
+            .collect()),
+        None => Err(WindowError::NoCompositor),
+    }
+}
+
+// messages are the magic string, the payload length and type in native byte order, then the payload
+fn i3_request(socket: &Path, kind: u32, payload: &str) -> Result<Value> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+
+    let mut message = I3_IPC_MAGIC.to_vec();
+    message.extend((payload.len() as u32).to_ne_bytes());
+    message.extend(kind.to_ne_bytes());
+    message.extend(payload.as_bytes());
+    stream.write_all(&message)?;
+
+    let mut header = [0u8; 14];
+    stream.read_exact(&mut header)?;
+    if &header[..I3_IPC_MAGIC.len()] != I3_IPC_MAGIC {
+        return Err(WindowError::Protocol("missing i3-ipc header".to_owned()));
+    }
+
+    let length = u32::from_ne_bytes(header[6..10].try_into().unwrap());
+    let mut reply = vec![0u8; length as usize];
+    stream.read_exact(&mut reply)?;
+    Ok(serde_json::from_slice(&reply)?)
+}
+
+// windows are the leaves of the tree that have a process, tiled or floating
+fn collect_i3_windows(node: &Value, windows: &mut Vec<Window>) {
+    let children: Vec<&Value> = ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[key].as_array())
+        .flatten()
+        .collect();
+
+    if children.is_empty() && (node["pid"].is_u64() || node["window"].is_u64()) {
+        if node["pid"].as_u64() == Some(process::id() as u64) {
+            return;
+        }
+
+        // xwayland windows and i3 only have the X11 class
+        let app_id = node["app_id"]
+            .as_str()
+            .or(node["window_properties"]["class"].as_str())
+            .unwrap_or_default();
+        if let Some(id) = node["id"].as_i64() {
+            windows.push(Window {
+                id: WindowId::Con(id),
+                app_id: app_id.to_string(),
+                title: node["name"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        return;
+    }
+
+    for child in children {
+        collect_i3_windows(child, windows);
+    }
+}
+
+fn hyprland_request(socket: &Path, request: &str) -> Result<String> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+    stream.write_all(request.as_bytes())?;
+
+    // it closes the connection after replying
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
+
+fn list_hyprland_windows(socket: &Path) -> Result<Vec<Window>> {
+    let clients: Value = serde_json::from_str(&hyprland_request(socket, "j/clients")?)?;
+    let clients = clients
+        .as_array()
+        .ok_or(WindowError::Protocol("expected a list of clients".to_owned()))?;
+
+    Ok(clients
+        .iter()
+        .filter(|it| it["mapped"].as_bool().unwrap_or(true) && !it["hidden"].as_bool().unwrap_or(false))
+        .filter(|it| it["pid"].as_u64() != Some(process::id() as u64))
+        .filter_map(|it| {
+            Some(Window {
+                id: WindowId::Address(it["address"].as_str()?.to_string()),
+                app_id: it["class"].as_str().unwrap_or_default().to_string(),
+                title: it["title"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug)]
+struct Toplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    app_id: String,
+    title: String,
+}
+
+#[derive(Debug, Default)]
+struct ToplevelState {
+    manager: Option<ZwlrForeignToplevelManagerV1>,
+    seat: Option<WlSeat>,
+    toplevels: Vec<Toplevel>,
+}
+
+// the connection has to outlive the handles for them to be activated
+struct ToplevelList {
+    connection: Connection,
+    state: ToplevelState,
+}
+
+impl Dispatch<wl_registry::WlRegistry, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        registry: &wl_registry::WlRegistry,
+        event: wl_registry::Event,
+        _: &(),
+        _: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            match interface.as_str() {
+                "zwlr_foreign_toplevel_manager_v1" => {
+                    state.manager = Some(registry.bind(
+                        name,
+                        version.min(FOREIGN_TOPLEVEL_MANAGER_VERSION),
+                        qh,
+                        (),
+                    ))
+                }
+                "wl_seat" if state.seat.is_none() => {
+                    state.seat = Some(registry.bind(name, 1, qh, ()))
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.push(Toplevel {
+                handle: toplevel,
+                app_id: String::new(),
+                title: String::new(),
+            });
+        }
+    }
+
+    event_created_child!(ToplevelState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for ToplevelState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let toplevel = state.toplevels.iter_mut().find(|it| &it.handle == handle);
+        match (event, toplevel) {
+            (zwlr_foreign_toplevel_handle_v1::Event::Title { title }, Some(toplevel)) => {
+                toplevel.title = title
+            }
+            (zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id }, Some(toplevel)) => {
+                toplevel.app_id = app_id
+            }
+            (zwlr_foreign_toplevel_handle_v1::Event::Closed, _) => {
+                state.toplevels.retain(|it| &it.handle != handle)
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for ToplevelState {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+fn list_toplevels() -> Result<ToplevelList> {
+    let connection =
+        Connection::connect_to_env().map_err(|e| WindowError::Wayland(e.to_string()))?;
+    let mut queue = connection.new_event_queue();
+    connection.display().get_registry(&queue.handle(), ());
+
+    let mut state = ToplevelState::default();
+    // first the globals, then the toplevels the manager announces when bound
+    for _ in 0..2 {
+        queue
+            .roundtrip(&mut state)
+            .map_err(|e| WindowError::Wayland(e.to_string()))?;
+    }
+    if state.manager.is_none() {
+        return Err(WindowError::NoCompositor);
+    }
+
+    Ok(ToplevelList { connection, state })
+}
+
+fn activate_toplevel(index: usize, window: &Window) -> Result<()> {
+    let list = list_toplevels()?;
+    let same_window = |it: &&Toplevel| it.app_id == window.app_id && it.title == window.title;
+    // windows opened or closed meanwhile shift the positions
+    let toplevel = list
+        .state
+        .toplevels
+        .get(index)
+        .filter(same_window)
+        .or(list.state.toplevels.iter().find(same_window))
+        .ok_or(WindowError::NotFound)?;
+    let seat = list.state.seat.as_ref().ok_or(WindowError::NoCompositor)?;
+
+    toplevel.handle.activate(seat);
+    list.connection
+        .roundtrip()
+        .map_err(|e| WindowError::Wayland(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::net::UnixListener, thread::JoinHandle};
+
+    use serde_json::json;
+
+    use super::*;
+
+    // a socket in the temp dir, unique per test so they can run in parallel
+    fn socket_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("automata-windowmgr-{}-{}.sock", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    // answers one i3 request with the reply, returning the type and payload it got
+    fn serve_i3(socket: &Path, magic: &'static [u8], reply: Value) -> JoinHandle<(u32, String)> {
+        let listener = UnixListener::bind(socket).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut header = [0u8; 14];
+            stream.read_exact(&mut header).unwrap();
+            let length = u32::from_ne_bytes(header[6..10].try_into().unwrap());
+            let kind = u32::from_ne_bytes(header[10..14].try_into().unwrap());
+            let mut payload = vec![0u8; length as usize];
+            stream.read_exact(&mut payload).unwrap();
+
+            let reply = reply.to_string();
+            let mut message = magic.to_vec();
+            message.extend((reply.len() as u32).to_ne_bytes());
+            message.extend(kind.to_ne_bytes());
+            message.extend(reply.as_bytes());
+            stream.write_all(&message).unwrap();
+            (kind, String::from_utf8(payload).unwrap())
+        })
+    }
+
+    // answers one Hyprland request and closes the connection, like Hyprland does
+    fn serve_hyprland(socket: &Path, reply: Value) -> JoinHandle<String> {
+        let listener = UnixListener::bind(socket).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 256];
+            let length = stream.read(&mut request).unwrap();
+            stream.write_all(reply.to_string().as_bytes()).unwrap();
+            String::from_utf8(request[..length].to_vec()).unwrap()
+        })
+    }
+
+    fn window(id: WindowId, app_id: &str, title: &str) -> Window {
+        Window {
+            id,
+            app_id: app_id.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn i3_request_sends_the_type_and_payload() {
+        let socket = socket_path("i3-request");
+        let server = serve_i3(&socket, I3_IPC_MAGIC, json!([{ "success": true }]));
+
+        let reply = i3_request(&socket, I3_RUN_COMMAND, "[con_id=7] focus").unwrap();
+
+        assert_eq!(reply, json!([{ "success": true }]));
+        assert_eq!(server.join().unwrap(), (I3_RUN_COMMAND, "[con_id=7] focus".to_owned()));
+        fs::remove_file(&socket).unwrap();
+    }
+
+    #[test]
+    fn i3_request_rejects_replies_without_the_magic() {
+        let socket = socket_path("i3-magic");
+        let server = serve_i3(&socket, b"i3-ipx", json!({}));
+
+        let result = i3_request(&socket, I3_GET_TREE, "");
+
+        assert!(matches!(result, Err(WindowError::Protocol(_))));
+        server.join().unwrap();
+        fs::remove_file(&socket).unwrap();
+    }
+
+    #[test]
+    fn collect_i3_windows_finds_tiled_floating_and_xwayland_leaves() {
+        let tree = json!({
+            "id": 1,
+            "name": "root",
+            "nodes": [{
+                "id": 2,
+                "name": "1",
+                "type": "workspace",
+                "nodes": [
+                    { "id": 10, "name": "Terminal", "pid": 100, "app_id": "foot", "nodes": [] },
+                    {
+                        "id": 11,
+                        "name": "split",
+                        "nodes": [{
+                            "id": 12,
+                            "name": "Steam",
+                            "pid": 101,
+                            "app_id": null,
+                            "window": 4194305,
+                            "window_properties": { "class": "steam" },
+                        }],
+                    },
+                    { "id": 13, "name": "Launcher", "pid": process::id(), "app_id": APPLICATION_ID },
+                ],
+                "floating_nodes": [
+                    { "id": 14, "name": "Calculator", "pid": 102, "app_id": "org.gnome.Calculator" },
+                ],
+            }, {
+                // an empty workspace has no process, it's not a window
+                "id": 3,
+                "name": "2",
+                "type": "workspace",
+                "nodes": [],
+                "floating_nodes": [],
+            }],
+        });
+
+        let mut windows = vec![];
+        collect_i3_windows(&tree, &mut windows);
+
+        assert_eq!(
+            windows,
+            vec![
+                window(WindowId::Con(10), "foot", "Terminal"),
+                window(WindowId::Con(12), "steam", "Steam"),
+                window(WindowId::Con(14), "org.gnome.Calculator", "Calculator"),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_hyprland_windows_skips_hidden_unmapped_and_own_windows() {
+        let socket = socket_path("hyprland-clients");
+        let server = serve_hyprland(
+            &socket,
+            json!([
+                { "address": "0x1", "class": "firefox", "title": "Mozilla Firefox", "pid": 200 },
+                { "address": "0x2", "class": "kitty", "title": "hidden", "pid": 201, "hidden": true },
+                { "address": "0x3", "class": "kitty", "title": "unmapped", "pid": 202, "mapped": false },
+                { "address": "0x4", "class": APPLICATION_ID, "title": "Launcher", "pid": process::id() },
+                { "class": "broken", "title": "no address", "pid": 203 },
+                { "address": "0x5", "class": "kitty", "title": "~", "pid": 204, "mapped": true },
+            ]),
+        );
+
+        let windows = list_hyprland_windows(&socket).unwrap();
+
+        assert_eq!(server.join().unwrap(), "j/clients");
+        assert_eq!(
+            windows,
+            vec![
+                window(WindowId::Address("0x1".to_owned()), "firefox", "Mozilla Firefox"),
+                window(WindowId::Address("0x5".to_owned()), "kitty", "~"),
+            ]
+        );
+        fs::remove_file(&socket).unwrap();
+    }
+
+    #[test]
+    fn list_hyprland_windows_rejects_other_replies() {
+        let socket = socket_path("hyprland-error");
+        let server = serve_hyprland(&socket, json!({ "error": "unknown request" }));
+
+        let result = list_hyprland_windows(&socket);
+
+        assert!(matches!(result, Err(WindowError::Protocol(_))));
+        server.join().unwrap();
+        fs::remove_file(&socket).unwrap();
+    }
+
+    #[test]
+    fn belongs_to_matches_names_ignoring_case() {
+        let firefox = window(WindowId::Con(1), "Firefox", "Mozilla Firefox");
+
+        assert!(firefox.belongs_to(&["firefox".to_owned()]));
+        assert!(firefox.belongs_to(&["Chromium".to_owned(), "FIREFOX".to_owned()]));
+        assert!(!firefox.belongs_to(&["firefox-esr".to_owned()]));
+        assert!(!firefox.belongs_to(&[]));
+    }
+
+    #[test]
+    fn belongs_to_matches_the_last_part_of_desktop_file_ids() {
+        let nautilus = window(WindowId::Con(1), "nautilus", "Home");
+        let reverse_dns = window(WindowId::Con(2), "org.gnome.Nautilus", "Home");
+
+        assert!(nautilus.belongs_to(&["org.gnome.Nautilus".to_owned()]));
+        assert!(reverse_dns.belongs_to(&["org.gnome.Nautilus".to_owned()]));
+        assert!(!reverse_dns.belongs_to(&["nautilus".to_owned()]));
+    }
+
+    #[test]
+    fn belongs_to_never_matches_windows_without_an_app_id() {
+        let untitled = window(WindowId::Toplevel(0), "", "untitled");
+
+        assert!(!untitled.belongs_to(&["".to_owned()]));
+        assert!(!untitled.belongs_to(&["org.example.".to_owned()]));
+    }
+}